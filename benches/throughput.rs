@@ -0,0 +1,114 @@
+// compares reading a large amount of output from a child process through
+// the tokio pty (which waits for readiness via AsyncFd and then calls read)
+// against the io_uring pty (which submits the read directly, either into an
+// ordinary buffer or into one registered with the kernel). the byte
+// counts aren't checked exactly, since linux can occasionally report the
+// hangup before the last few blocks of output have made it to the master.
+
+const BYTES: usize = 4 * 1024 * 1024;
+const BUFFER_SIZE: usize = 64 * 1024;
+
+fn spawn_writer(pts: &pty_process::Pts) -> tokio::process::Child {
+    pty_process::Command::new("head")
+        .arg("-c")
+        .arg(BYTES.to_string())
+        .arg("/dev/zero")
+        .spawn(pts)
+        .unwrap()
+}
+
+async fn read_async_fd() -> usize {
+    use tokio::io::AsyncReadExt as _;
+
+    let mut pty = pty_process::Pty::new().unwrap();
+    let pts = pty.pts().unwrap();
+    let mut child = spawn_writer(&pts);
+    drop(pts);
+
+    let mut buf = vec![0; BUFFER_SIZE];
+    let mut total = 0;
+    loop {
+        let bytes = pty.read(&mut buf).await.unwrap();
+        if bytes == 0 {
+            break;
+        }
+        total += bytes;
+    }
+    child.wait().await.unwrap();
+    total
+}
+
+async fn read_uring() -> usize {
+    let pty = pty_process::uring::Pty::new().unwrap();
+    let pts = pty.pts().unwrap();
+    let mut child = spawn_writer(&pts);
+    drop(pts);
+
+    let mut buf = Vec::with_capacity(BUFFER_SIZE);
+    let mut total = 0;
+    loop {
+        let (res, returned) = pty.read(buf).await;
+        buf = returned;
+        let bytes = res.unwrap();
+        if bytes == 0 {
+            break;
+        }
+        total += bytes;
+    }
+    child.wait().await.unwrap();
+    total
+}
+
+async fn read_uring_fixed() -> usize {
+    let registry =
+        tokio_uring::buf::fixed::FixedBufRegistry::new([Vec::with_capacity(
+            BUFFER_SIZE,
+        )]);
+    registry.register().unwrap();
+
+    let pty = pty_process::uring::Pty::new().unwrap();
+    let pts = pty.pts().unwrap();
+    let mut child = spawn_writer(&pts);
+    drop(pts);
+
+    let mut buf = registry.check_out(0).unwrap();
+    let mut total = 0;
+    loop {
+        let (res, returned) = pty.read_fixed(buf).await;
+        buf = returned;
+        let bytes = res.unwrap();
+        if bytes == 0 {
+            break;
+        }
+        total += bytes;
+    }
+    child.wait().await.unwrap();
+    total
+}
+
+fn throughput(c: &mut criterion::Criterion) {
+    let mut group = c.benchmark_group("throughput");
+    group.throughput(criterion::Throughput::Bytes(BYTES as u64));
+    group.sample_size(20);
+
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    group.bench_function("async_fd", |b| {
+        b.iter(|| rt.block_on(read_async_fd()));
+    });
+
+    let rt = tokio_uring::Runtime::new(&tokio_uring::builder()).unwrap();
+    group.bench_function("uring", |b| {
+        b.iter(|| rt.block_on(read_uring()));
+    });
+    group.bench_function("uring_fixed", |b| {
+        b.iter(|| rt.block_on(read_uring_fixed()));
+    });
+
+    group.finish();
+}
+
+criterion::criterion_group!(benches, throughput);
+criterion::criterion_main!(benches);
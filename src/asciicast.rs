@@ -0,0 +1,562 @@
+//! Recording and playback of sessions in the
+//! [asciicast v2](https://docs.asciinema.org/manual/asciicast/v2/) format.
+//!
+//! A [`Recording`] wraps a [`Pty`](crate::Pty) and writes everything read
+//! from it (and optionally everything written to it, as well as any resize
+//! events) to the given writer as an asciicast v2 stream. A [`Player`] reads
+//! such a stream back and can replay it into a writer or a pty.
+//!
+//! ```no_run
+//! # #[cfg(feature = "async")]
+//! # #[tokio::main]
+//! # async fn main() -> pty_process::Result<()> {
+//! let pty = pty_process::Pty::new()?;
+//! pty.resize(pty_process::Size::new(24, 80))?;
+//! let mut cmd = pty_process::Command::new("nethack");
+//! let child = cmd.spawn(&pty.pts()?)?;
+//! let file = tokio::fs::File::create("nethack.cast").await?;
+//! let mut recording = pty_process::asciicast::Recording::new(pty, file)?;
+//! // read from and write to `recording` as you would the pty itself
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "async"))]
+//! # fn main() {}
+//! ```
+//!
+//! See [`blocking::asciicast`](crate::blocking::asciicast) for the blocking
+//! equivalent.
+
+/// The header of an asciicast stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+    size: crate::Size,
+    timestamp: Option<u64>,
+}
+
+impl Header {
+    /// Returns the initial terminal size of the recording.
+    #[must_use]
+    pub fn size(&self) -> crate::Size {
+        self.size
+    }
+
+    /// Returns the time at which the recording started, as seconds since
+    /// the Unix epoch, if known.
+    #[must_use]
+    pub fn timestamp(&self) -> Option<u64> {
+        self.timestamp
+    }
+}
+
+/// An event in an asciicast stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// Data written by the child process to the pty.
+    Output(Vec<u8>),
+    /// Data written to the pty to be read by the child process.
+    Input(Vec<u8>),
+    /// The terminal was resized.
+    Resize(crate::Size),
+    /// A marker, with an optional label.
+    Marker(String),
+}
+
+/// The speed at which a [`Player`] replays a recording.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Speed {
+    /// Replay events with the same timing as they were recorded with.
+    RealTime,
+    /// Replay events with the recorded delays divided by the given factor
+    /// (so `Scaled(2.0)` plays back at double speed). The factor must be
+    /// positive and finite, or playback will fail with an error.
+    Scaled(f64),
+    /// Replay all events immediately.
+    Instant,
+}
+
+impl Speed {
+    pub(crate) fn scale(
+        self,
+        time: std::time::Duration,
+    ) -> std::io::Result<std::time::Duration> {
+        match self {
+            Self::RealTime => Ok(time),
+            Self::Scaled(factor) if factor > 0.0 && factor.is_finite() => {
+                std::time::Duration::try_from_secs_f64(
+                    time.as_secs_f64() / factor,
+                )
+                .map_err(|_| invalid_input("scaled event time is too large"))
+            }
+            Self::Scaled(_) => Err(invalid_input(
+                "playback speed must be positive and finite",
+            )),
+            Self::Instant => Ok(std::time::Duration::ZERO),
+        }
+    }
+}
+
+/// Writes events to a stream in the asciicast v2 format, timestamped
+/// relative to the creation of the recorder.
+pub struct Recorder<W: std::io::Write> {
+    writer: W,
+    start: std::time::Instant,
+    output: Vec<u8>,
+    input: Vec<u8>,
+}
+
+impl<W: std::io::Write> Recorder<W> {
+    /// Creates a new recorder, and writes the asciicast header describing a
+    /// terminal of the given size to `writer`.
+    ///
+    /// # Errors
+    /// Returns an error if writing the header failed.
+    pub fn new(mut writer: W, size: crate::Size) -> std::io::Result<Self> {
+        let mut header = serde_json::json!({
+            "version": 2,
+            "width": size.cols(),
+            "height": size.rows(),
+        });
+        if let Ok(timestamp) =
+            std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)
+        {
+            header["timestamp"] = timestamp.as_secs().into();
+        }
+        serde_json::to_writer(&mut writer, &header)?;
+        writer.write_all(b"\n")?;
+        Ok(Self {
+            writer,
+            start: std::time::Instant::now(),
+            output: vec![],
+            input: vec![],
+        })
+    }
+
+    /// Records data written by the child process. Incomplete UTF-8
+    /// sequences at the end of `data` are held back until the rest of the
+    /// sequence is recorded.
+    ///
+    /// # Errors
+    /// Returns an error if writing the event failed.
+    pub fn record_output(&mut self, data: &[u8]) -> std::io::Result<()> {
+        self.output.extend_from_slice(data);
+        let data = take_utf8(&mut self.output);
+        self.write_event("o", &data)
+    }
+
+    /// Records data sent to the child process. Incomplete UTF-8 sequences
+    /// at the end of `data` are held back until the rest of the sequence is
+    /// recorded.
+    ///
+    /// # Errors
+    /// Returns an error if writing the event failed.
+    pub fn record_input(&mut self, data: &[u8]) -> std::io::Result<()> {
+        self.input.extend_from_slice(data);
+        let data = take_utf8(&mut self.input);
+        self.write_event("i", &data)
+    }
+
+    /// Records a change in the terminal size.
+    ///
+    /// # Errors
+    /// Returns an error if writing the event failed.
+    pub fn record_resize(
+        &mut self,
+        size: crate::Size,
+    ) -> std::io::Result<()> {
+        self.write_event("r", &size.to_string())
+    }
+
+    /// Records a marker with the given label.
+    ///
+    /// # Errors
+    /// Returns an error if writing the event failed.
+    pub fn record_marker(&mut self, label: &str) -> std::io::Result<()> {
+        self.write_event("m", label)
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn write_event(&mut self, code: &str, data: &str) -> std::io::Result<()> {
+        if data.is_empty() && code != "m" {
+            return Ok(());
+        }
+        let time = self.start.elapsed().as_secs_f64();
+        serde_json::to_writer(&mut self.writer, &(time, code, data))?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()
+    }
+}
+
+/// Removes and returns the longest prefix of `buf` which can be represented
+/// as a string, replacing invalid sequences with U+FFFD but leaving an
+/// incomplete sequence at the end of `buf` in place.
+fn take_utf8(buf: &mut Vec<u8>) -> String {
+    let mut s = String::new();
+    let mut rest = &buf[..];
+    loop {
+        match std::str::from_utf8(rest) {
+            Ok(valid) => {
+                s.push_str(valid);
+                rest = &[];
+                break;
+            }
+            Err(e) => {
+                let (valid, invalid) = rest.split_at(e.valid_up_to());
+                // Safety: from_utf8 just verified this prefix
+                s.push_str(unsafe { std::str::from_utf8_unchecked(valid) });
+                if let Some(len) = e.error_len() {
+                    s.push(char::REPLACEMENT_CHARACTER);
+                    rest = &invalid[len..];
+                } else {
+                    rest = invalid;
+                    break;
+                }
+            }
+        }
+    }
+    let consumed = buf.len() - rest.len();
+    buf.drain(..consumed);
+    s
+}
+
+/// Parses the header line of an asciicast v2 stream.
+pub(crate) fn parse_header(line: &str) -> std::io::Result<Header> {
+    let header: serde_json::Value = serde_json::from_str(line)?;
+    if header["version"] != 2 {
+        return Err(invalid_data("unsupported asciicast version"));
+    }
+    let width = header["width"]
+        .as_u64()
+        .and_then(|n| u16::try_from(n).ok())
+        .ok_or_else(|| invalid_data("invalid width in header"))?;
+    let height = header["height"]
+        .as_u64()
+        .and_then(|n| u16::try_from(n).ok())
+        .ok_or_else(|| invalid_data("invalid height in header"))?;
+    Ok(Header {
+        size: crate::Size::new(height, width),
+        timestamp: header["timestamp"].as_u64(),
+    })
+}
+
+/// Parses an event line of an asciicast v2 stream.
+pub(crate) fn parse_event(
+    line: &str,
+) -> std::io::Result<(std::time::Duration, Event)> {
+    let (time, code, data): (f64, String, String) =
+        serde_json::from_str(line)?;
+    let time = std::time::Duration::try_from_secs_f64(time)
+        .map_err(|_| invalid_data("invalid event time"))?;
+    let event = match code.as_str() {
+        "o" => Event::Output(data.into_bytes()),
+        "i" => Event::Input(data.into_bytes()),
+        "r" => Event::Resize(
+            data.parse()
+                .map_err(|_| invalid_data("invalid resize event"))?,
+        ),
+        "m" => Event::Marker(data),
+        _ => return Err(invalid_data("unknown event type")),
+    };
+    Ok((time, event))
+}
+
+/// Reads events from a stream in the asciicast v2 format.
+///
+/// The stream is read asynchronously, so recordings can be played back
+/// from files or pipes without blocking the runtime.
+#[cfg(feature = "async")]
+pub struct Player<R: tokio::io::AsyncBufRead + Unpin> {
+    reader: R,
+    header: Header,
+}
+
+#[cfg(feature = "async")]
+impl<R: tokio::io::AsyncBufRead + Unpin> Player<R> {
+    /// Creates a new player, reading the asciicast header from `reader`.
+    ///
+    /// # Errors
+    /// Returns an error if reading from `reader` failed, or if the header
+    /// is not a valid asciicast v2 header.
+    pub async fn new(mut reader: R) -> std::io::Result<Self> {
+        use tokio::io::AsyncBufReadExt as _;
+
+        let mut line = String::new();
+        reader.read_line(&mut line).await?;
+        let header = parse_header(&line)?;
+        Ok(Self { reader, header })
+    }
+
+    /// Returns the header of the recording.
+    #[must_use]
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Reads the next event from the recording, along with its time relative
+    /// to the start of the recording. Returns `None` at the end of the
+    /// stream.
+    ///
+    /// # Errors
+    /// Returns an error if reading from the underlying reader failed, or if
+    /// the event could not be parsed.
+    pub async fn next_event(
+        &mut self,
+    ) -> std::io::Result<Option<(std::time::Duration, Event)>> {
+        use tokio::io::AsyncBufReadExt as _;
+
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if self.reader.read_line(&mut line).await? == 0 {
+                return Ok(None);
+            }
+            if !line.trim().is_empty() {
+                break;
+            }
+        }
+        parse_event(&line).map(Some)
+    }
+
+    /// Replays the output events of the recording into `writer`, at the
+    /// given speed. Other events are ignored.
+    ///
+    /// # Errors
+    /// Returns an error if reading the recording or writing to `writer`
+    /// failed, or if `speed` is invalid.
+    pub async fn play<W: tokio::io::AsyncWrite + Unpin>(
+        &mut self,
+        mut writer: W,
+        speed: Speed,
+    ) -> std::io::Result<()> {
+        use tokio::io::AsyncWriteExt as _;
+
+        let start = tokio::time::Instant::now();
+        while let Some((time, event)) = self.next_event().await? {
+            tokio::time::sleep_until(start + speed.scale(time)?).await;
+            if let Event::Output(data) = event {
+                writer.write_all(&data).await?;
+                writer.flush().await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Replays the recording into the given pty, at the given speed. Output
+    /// events are written to the pty and resize events resize it.
+    ///
+    /// # Errors
+    /// Returns an error if reading the recording, writing to the pty, or
+    /// resizing the pty failed, or if `speed` is invalid.
+    pub async fn play_pty(
+        &mut self,
+        pty: &mut crate::Pty,
+        speed: Speed,
+    ) -> crate::Result<()> {
+        use tokio::io::AsyncWriteExt as _;
+
+        pty.resize(self.header.size)?;
+        let start = tokio::time::Instant::now();
+        while let Some((time, event)) = self.next_event().await? {
+            tokio::time::sleep_until(start + speed.scale(time)?).await;
+            match event {
+                Event::Output(data) => pty.write_all(&data).await?,
+                Event::Resize(size) => pty.resize(size)?,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+fn invalid_data(msg: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, msg)
+}
+
+fn invalid_input(msg: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidInput, msg)
+}
+
+/// Wrapper around a [`Pty`](crate::Pty) which records the session in the
+/// asciicast v2 format.
+///
+/// All data read from the pty is recorded as output. Data written to the
+/// pty is recorded as input if enabled via
+/// [`set_record_input`](Self::set_record_input), and calls to
+/// [`resize`](Self::resize) are recorded as resize events.
+///
+/// Events are buffered in memory and written to the underlying writer as
+/// the recording is read from or written to, without blocking the
+/// executor. Call [`flush`](tokio::io::AsyncWriteExt::flush) before
+/// [`into_inner`](Self::into_inner) to make sure that all recorded events
+/// have been written.
+#[cfg(feature = "async")]
+pub struct Recording<W: tokio::io::AsyncWrite + Unpin> {
+    pty: crate::Pty,
+    writer: W,
+    recorder: Recorder<Vec<u8>>,
+    record_input: bool,
+}
+
+#[cfg(feature = "async")]
+impl<W: tokio::io::AsyncWrite + Unpin> Recording<W> {
+    /// Starts recording the given pty to `writer`. The size in the header is
+    /// taken from the current size of the pty.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to retrieve the terminal size.
+    pub fn new(pty: crate::Pty, writer: W) -> crate::Result<Self> {
+        let recorder = Recorder::new(vec![], pty.size()?)?;
+        Ok(Self {
+            pty,
+            writer,
+            recorder,
+            record_input: false,
+        })
+    }
+
+    /// Sets whether data written to the pty should be recorded as input
+    /// events. Defaults to `false`.
+    pub fn set_record_input(&mut self, record_input: bool) {
+        self.record_input = record_input;
+    }
+
+    /// Change the terminal size associated with the pty, and record the
+    /// resize event.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to set the terminal size.
+    pub fn resize(&mut self, size: crate::Size) -> crate::Result<()> {
+        self.pty.resize(size)?;
+        self.recorder.record_resize(size)?;
+        Ok(())
+    }
+
+    /// Returns a mutable reference to the underlying recorder, for instance
+    /// to add markers. Events recorded through it are buffered along with
+    /// the rest of the recording.
+    pub fn recorder(&mut self) -> &mut Recorder<Vec<u8>> {
+        &mut self.recorder
+    }
+
+    /// Returns a reference to the underlying pty.
+    #[must_use]
+    pub fn get_ref(&self) -> &crate::Pty {
+        &self.pty
+    }
+
+    /// Stops recording, returning the underlying pty and writer. Any events
+    /// which have not yet been written are discarded.
+    pub fn into_inner(self) -> (crate::Pty, W) {
+        (self.pty, self.writer)
+    }
+
+    /// Writes as many of the buffered events to the underlying writer as
+    /// possible, returning `Ready` once there are none left.
+    fn poll_write_events(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        let buf = &mut self.recorder.writer;
+        while !buf.is_empty() {
+            match std::pin::Pin::new(&mut self.writer).poll_write(cx, buf) {
+                std::task::Poll::Ready(Ok(0)) => {
+                    return std::task::Poll::Ready(Err(
+                        std::io::ErrorKind::WriteZero.into(),
+                    ))
+                }
+                std::task::Poll::Ready(Ok(bytes)) => {
+                    buf.drain(..bytes);
+                }
+                std::task::Poll::Ready(Err(e)) => {
+                    return std::task::Poll::Ready(Err(e))
+                }
+                std::task::Poll::Pending => return std::task::Poll::Pending,
+            }
+        }
+        std::task::Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(feature = "async")]
+impl<W: tokio::io::AsyncWrite + Unpin> tokio::io::AsyncRead for Recording<W> {
+    fn poll_read(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &mut tokio::io::ReadBuf,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        let filled = buf.filled().len();
+        let this = &mut *self;
+        match std::pin::Pin::new(&mut this.pty).poll_read(cx, buf) {
+            std::task::Poll::Ready(Ok(())) => {
+                this.recorder.record_output(&buf.filled()[filled..])?;
+            }
+            res => return res,
+        }
+        // the data has already been read, so if the writer isn't ready yet
+        // the events will be written on a later call
+        match this.poll_write_events(cx) {
+            std::task::Poll::Ready(Err(e)) => std::task::Poll::Ready(Err(e)),
+            _ => std::task::Poll::Ready(Ok(())),
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<W: tokio::io::AsyncWrite + Unpin> tokio::io::AsyncWrite
+    for Recording<W>
+{
+    fn poll_write(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &[u8],
+    ) -> std::task::Poll<std::io::Result<usize>> {
+        let this = &mut *self;
+        let bytes =
+            match std::pin::Pin::new(&mut this.pty).poll_write(cx, buf) {
+                std::task::Poll::Ready(Ok(bytes)) => bytes,
+                res => return res,
+            };
+        if this.record_input {
+            this.recorder.record_input(&buf[..bytes])?;
+        }
+        match this.poll_write_events(cx) {
+            std::task::Poll::Ready(Err(e)) => std::task::Poll::Ready(Err(e)),
+            _ => std::task::Poll::Ready(Ok(bytes)),
+        }
+    }
+
+    fn poll_flush(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        let this = &mut *self;
+        match this.poll_write_events(cx) {
+            std::task::Poll::Ready(Ok(())) => {}
+            res => return res,
+        }
+        match std::pin::Pin::new(&mut this.writer).poll_flush(cx) {
+            std::task::Poll::Ready(Ok(())) => {}
+            res => return res,
+        }
+        std::pin::Pin::new(&mut this.pty).poll_flush(cx)
+    }
+
+    fn poll_shutdown(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), std::io::Error>> {
+        let this = &mut *self;
+        match this.poll_write_events(cx) {
+            std::task::Poll::Ready(Ok(())) => {}
+            res => return res,
+        }
+        match std::pin::Pin::new(&mut this.writer).poll_shutdown(cx) {
+            std::task::Poll::Ready(Ok(())) => {}
+            res => return res,
+        }
+        std::pin::Pin::new(&mut this.pty).poll_shutdown(cx)
+    }
+}
@@ -0,0 +1,77 @@
+/// Builder for a [`Pty`](crate::async_io::Pty) which is configured before it
+/// is returned
+///
+/// Obtained via [`Pty::builder`](crate::async_io::Pty::builder). Since the
+/// settings are all applied before any [`Pts`](crate::async_io::Pts) can be
+/// opened, a child process spawned on the resulting pty will see them from
+/// the start, rather than racing against a later call to
+/// [`resize`](crate::async_io::Pty::resize) or
+/// [`set_termios`](crate::async_io::Pty::set_termios).
+///
+/// ```no_run
+/// # fn main() -> pty_process::Result<()> {
+/// let pty = pty_process::async_io::Pty::builder()
+///     .size(pty_process::Size::new(24, 80))
+///     .echo(false)
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PtyBuilder {
+    options: crate::builder::Options,
+}
+
+impl PtyBuilder {
+    pub(crate) fn new() -> Self {
+        Self {
+            options: crate::builder::Options::default(),
+        }
+    }
+
+    /// Sets the initial terminal size of the pty.
+    pub fn size(&mut self, size: crate::Size) -> &mut Self {
+        self.options.size = Some(size);
+        self
+    }
+
+    /// Sets the initial terminal attributes of the pty. The
+    /// [`echo`](Self::echo) and [`raw`](Self::raw) settings are applied on
+    /// top of these.
+    pub fn termios(&mut self, termios: crate::Termios) -> &mut Self {
+        self.options.termios = Some(termios);
+        self
+    }
+
+    /// Sets whether input characters are initially echoed (`ECHO`).
+    pub fn echo(&mut self, echo: bool) -> &mut Self {
+        self.options.echo = Some(echo);
+        self
+    }
+
+    /// Sets whether the pty is initially in raw mode (see
+    /// [`Termios::make_raw`](crate::Termios::make_raw)).
+    pub fn raw(&mut self, raw: bool) -> &mut Self {
+        self.options.raw = raw;
+        self
+    }
+
+    /// Allocates the pty from the given `ptmx` device node (see
+    /// [`Pty::open_at`](crate::async_io::Pty::open_at)), rather than from
+    /// `/dev/ptmx`.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn ptmx(&mut self, ptmx: impl AsRef<std::path::Path>) -> &mut Self {
+        self.options.ptmx = Some(ptmx.as_ref().to_path_buf());
+        self
+    }
+
+    /// Allocates a new pty with the configured settings.
+    ///
+    /// # Errors
+    /// Returns an error if the pty failed to be allocated, if any of the
+    /// settings could not be applied, or if we were unable to register it
+    /// with the reactor.
+    pub fn build(&self) -> crate::Result<crate::async_io::Pty> {
+        crate::async_io::Pty::from_sys(self.options.open()?)
+    }
+}
@@ -0,0 +1,271 @@
+use async_process::unix::CommandExt as _;
+use std::os::unix::process::CommandExt as _;
+
+type PreExec = Box<dyn FnMut() -> std::io::Result<()> + Send + Sync>;
+
+/// State read by the `pre_exec` hook of the underlying command.
+/// `async_process::Command` has no way to add a `pre_exec` hook itself, so
+/// a single hook is installed on the [`std::process::Command`] it is created
+/// from, and each call to [`Command::spawn`] fills in what it should do.
+struct Hook {
+    pts_fd: std::sync::atomic::AtomicI32,
+    custom: std::sync::Mutex<Option<PreExec>>,
+}
+
+impl Hook {
+    fn run(&self) -> std::io::Result<()> {
+        crate::sys::session_leader(
+            self.pts_fd.load(std::sync::atomic::Ordering::SeqCst),
+        )?;
+        // the parent can't be holding this lock while spawning, since that
+        // requires a mutable reference to the command
+        if let Some(custom) = self
+            .custom
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .as_mut()
+        {
+            custom()?;
+        }
+        Ok(())
+    }
+}
+
+/// Wrapper around [`async_process::Command`]
+pub struct Command {
+    inner: async_process::Command,
+    stdin: bool,
+    stdout: bool,
+    stderr: bool,
+    hook: std::sync::Arc<Hook>,
+}
+
+impl Command {
+    /// See [`async_process::Command::new`]
+    pub fn new<S: AsRef<std::ffi::OsStr>>(program: S) -> Self {
+        let hook = std::sync::Arc::new(Hook {
+            pts_fd: std::sync::atomic::AtomicI32::new(-1),
+            custom: std::sync::Mutex::new(None),
+        });
+        let mut inner = std::process::Command::new(program);
+        let child_hook = std::sync::Arc::clone(&hook);
+        // Safety: setsid() is an async-signal-safe function and ioctl() is a
+        // raw syscall (which is inherently async-signal-safe). Locking the
+        // uncontended mutex doesn't allocate, and the custom hook is subject
+        // to the same requirements as any other pre_exec hook.
+        unsafe { inner.pre_exec(move || child_hook.run()) };
+        Self {
+            inner: inner.into(),
+            stdin: false,
+            stdout: false,
+            stderr: false,
+            hook,
+        }
+    }
+
+    /// See [`async_process::Command::arg`]
+    pub fn arg<S: AsRef<std::ffi::OsStr>>(&mut self, arg: S) -> &mut Self {
+        self.inner.arg(arg);
+        self
+    }
+
+    /// See [`async_process::Command::args`]
+    pub fn args<I, S>(&mut self, args: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<std::ffi::OsStr>,
+    {
+        self.inner.args(args);
+        self
+    }
+
+    /// See [`async_process::Command::env`]
+    pub fn env<K, V>(&mut self, key: K, val: V) -> &mut Self
+    where
+        K: AsRef<std::ffi::OsStr>,
+        V: AsRef<std::ffi::OsStr>,
+    {
+        self.inner.env(key, val);
+        self
+    }
+
+    /// See [`async_process::Command::envs`]
+    pub fn envs<I, K, V>(&mut self, vars: I) -> &mut Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<std::ffi::OsStr>,
+        V: AsRef<std::ffi::OsStr>,
+    {
+        self.inner.envs(vars);
+        self
+    }
+
+    /// See [`async_process::Command::env_remove`]
+    pub fn env_remove<K: AsRef<std::ffi::OsStr>>(
+        &mut self,
+        key: K,
+    ) -> &mut Self {
+        self.inner.env_remove(key);
+        self
+    }
+
+    /// See [`async_process::Command::env_clear`]
+    pub fn env_clear(&mut self) -> &mut Self {
+        self.inner.env_clear();
+        self
+    }
+
+    /// See [`async_process::Command::current_dir`]
+    pub fn current_dir<P: AsRef<std::path::Path>>(
+        &mut self,
+        dir: P,
+    ) -> &mut Self {
+        self.inner.current_dir(dir);
+        self
+    }
+
+    /// See [`async_process::Command::stdin`]
+    pub fn stdin<T: Into<std::process::Stdio>>(
+        &mut self,
+        cfg: T,
+    ) -> &mut Self {
+        self.stdin = true;
+        self.inner.stdin(cfg);
+        self
+    }
+
+    /// See [`async_process::Command::stdout`]
+    pub fn stdout<T: Into<std::process::Stdio>>(
+        &mut self,
+        cfg: T,
+    ) -> &mut Self {
+        self.stdout = true;
+        self.inner.stdout(cfg);
+        self
+    }
+
+    /// See [`async_process::Command::stderr`]
+    pub fn stderr<T: Into<std::process::Stdio>>(
+        &mut self,
+        cfg: T,
+    ) -> &mut Self {
+        self.stderr = true;
+        self.inner.stderr(cfg);
+        self
+    }
+
+    /// See [`async_process::Command::kill_on_drop`]
+    pub fn kill_on_drop(&mut self, kill_on_drop: bool) -> &mut Self {
+        self.inner.kill_on_drop(kill_on_drop);
+        self
+    }
+
+    /// Executes the command as a child process via
+    /// [`async_process::Command::spawn`] on the given pty. The pty will be
+    /// attached to all of `stdin`, `stdout`, and `stderr` of the child,
+    /// unless those file descriptors were previously overridden through calls
+    /// to [`stdin`](Self::stdin), [`stdout`](Self::stdout), or
+    /// [`stderr`](Self::stderr). The newly created child process will also be
+    /// made the session leader of a new session, and will have the given
+    /// pty set as its controlling terminal.
+    ///
+    /// # Errors
+    /// Returns an error if we fail to allocate new file descriptors for
+    /// attaching the pty to the child process, or if we fail to spawn the
+    /// child process (see the documentation for
+    /// [`async_process::Command::spawn`]), or if we fail to make the child a
+    /// session leader or set its controlling terminal.
+    pub fn spawn(
+        &mut self,
+        pts: &crate::async_io::Pts,
+    ) -> crate::Result<async_process::Child> {
+        let (stdin, stdout, stderr) = pts.0.setup_subprocess()?;
+
+        if !self.stdin {
+            self.inner.stdin(stdin);
+        }
+        if !self.stdout {
+            self.inner.stdout(stdout);
+        }
+        if !self.stderr {
+            self.inner.stderr(stderr);
+        }
+
+        self.hook.pts_fd.store(
+            std::os::fd::AsRawFd::as_raw_fd(&pts.0),
+            std::sync::atomic::Ordering::SeqCst,
+        );
+
+        Ok(self.inner.spawn()?)
+    }
+
+    /// Allocates a new pty with the given size, and executes the command as
+    /// a child process on it, as with [`spawn`](Self::spawn). The returned
+    /// [`PtyChild`](crate::async_io::PtyChild) owns both the pty and the
+    /// child process, and the child end of the pty is closed in the parent
+    /// once the child has been spawned.
+    ///
+    /// # Errors
+    /// Returns an error if the pty could not be allocated or resized, or if
+    /// spawning the child failed (see [`spawn`](Self::spawn)).
+    pub fn spawn_pty(
+        &mut self,
+        size: crate::Size,
+    ) -> crate::Result<crate::async_io::PtyChild> {
+        let pty = crate::async_io::Pty::builder().size(size).build()?;
+        let child = self.spawn(&pty.pts()?);
+        self.release_pts();
+        Ok(crate::async_io::PtyChild::new(pty, child?))
+    }
+
+    // spawn stores duplicates of the child end of the pty as the stdio of
+    // the underlying command, which would otherwise keep the pty from
+    // hanging up for as long as this command is alive
+    fn release_pts(&mut self) {
+        if !self.stdin {
+            self.inner.stdin(std::process::Stdio::null());
+        }
+        if !self.stdout {
+            self.inner.stdout(std::process::Stdio::null());
+        }
+        if !self.stderr {
+            self.inner.stderr(std::process::Stdio::null());
+        }
+    }
+
+    /// See [`async_process::unix::CommandExt::uid`]
+    pub fn uid(&mut self, id: u32) -> &mut Self {
+        self.inner.uid(id);
+        self
+    }
+
+    /// See [`async_process::unix::CommandExt::gid`]
+    pub fn gid(&mut self, id: u32) -> &mut Self {
+        self.inner.gid(id);
+        self
+    }
+
+    /// See [`std::os::unix::process::CommandExt::pre_exec`]
+    #[allow(clippy::missing_safety_doc)]
+    pub unsafe fn pre_exec<F>(&mut self, f: F) -> &mut Self
+    where
+        F: FnMut() -> std::io::Result<()> + Send + Sync + 'static,
+    {
+        *self
+            .hook
+            .custom
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) =
+            Some(Box::new(f));
+        self
+    }
+
+    /// See [`async_process::unix::CommandExt::arg0`]
+    pub fn arg0<S>(&mut self, arg: S) -> &mut Self
+    where
+        S: AsRef<std::ffi::OsStr>,
+    {
+        self.inner.arg0(arg);
+        self
+    }
+}
@@ -0,0 +1,22 @@
+//! Equivalents for [`pty_process::Command`](crate::Command) and
+//! [`pty_process::Pty`](crate::Pty) built on `async-io` rather than tokio
+//!
+//! This allows using ptys with runtimes such as `smol` and `async-std`.
+//! The pty types implement [`futures_io::AsyncRead`] and
+//! [`futures_io::AsyncWrite`], and [`Command::spawn`] returns an
+//! [`async_process::Child`] (or [`Command::spawn_pty`] returns a
+//! [`PtyChild`] which owns both the pty and the child).
+//!
+//! The integrations built on the tokio types ([`interact`](crate::interact),
+//! [`ResizeForwarder`](crate::ResizeForwarder), and the optional
+//! `asciicast`, `expect`, `screen`, and `script` modules) are not provided
+//! for this backend.
+
+mod builder;
+pub use builder::PtyBuilder;
+mod child;
+pub use child::PtyChild;
+mod command;
+pub use command::Command;
+mod pty;
+pub use pty::{OwnedReadPty, OwnedWritePty, Pts, Pty, ReadPty, WritePty};
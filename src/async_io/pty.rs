@@ -0,0 +1,879 @@
+#![allow(clippy::module_name_repetitions)]
+
+use std::io::{Read as _, Write as _};
+
+type AsyncPty = async_io::Async<crate::sys::Pty>;
+
+/// An allocated pty
+#[derive(Debug)]
+pub struct Pty(AsyncPty);
+
+impl Pty {
+    /// Allocate and return a new pty.
+    ///
+    /// # Errors
+    /// Returns an error if the pty failed to be allocated, or if we were
+    /// unable to register it with the reactor.
+    pub fn new() -> crate::Result<Self> {
+        Self::from_sys(crate::sys::Pty::open()?)
+    }
+
+    /// Allocate and return a new pty from the given `ptmx` device node. See
+    /// [`Pty::open_at`](crate::blocking::Pty::open_at).
+    ///
+    /// # Errors
+    /// Returns an error if the device node could not be opened, or if it
+    /// is not a `ptmx` device, or if we were unable to register it with the
+    /// reactor.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn open_at(ptmx: impl AsRef<std::path::Path>) -> crate::Result<Self> {
+        Self::from_sys(crate::sys::Pty::open_at(ptmx.as_ref())?)
+    }
+
+    /// Creates a pty from a file descriptor for the parent end of an existing
+    /// pty, such as one received from another process. The file descriptor
+    /// is put into non-blocking mode (note that this also affects any other
+    /// file descriptors for the same pty, including in other processes).
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to register the file descriptor
+    /// with the reactor.
+    pub fn from_fd(fd: std::os::fd::OwnedFd) -> crate::Result<Self> {
+        Self::from_sys(crate::sys::Pty::from_fd(fd))
+    }
+
+    /// Returns a builder which can be used to configure the size and
+    /// terminal attributes of a new pty before it is allocated. See
+    /// [`PtyBuilder`](crate::async_io::PtyBuilder).
+    #[must_use]
+    pub fn builder() -> crate::async_io::PtyBuilder {
+        crate::async_io::PtyBuilder::new()
+    }
+
+    pub(crate) fn from_sys(pty: crate::sys::Pty) -> crate::Result<Self> {
+        // Async::new also puts the file descriptor into non-blocking mode
+        Ok(Self(async_io::Async::new(pty)?))
+    }
+
+    /// Returns the current state of the pty, to be sent along with it via
+    /// [`send_to`](Self::send_to).
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to retrieve the terminal size,
+    /// the terminal attributes, or the index of the pty.
+    pub fn metadata(&self) -> crate::Result<crate::PtyMetadata> {
+        crate::PtyMetadata::new(self.0.get_ref())
+    }
+
+    /// Sends the pty to another process over a unix socket (as
+    /// `SCM_RIGHTS` ancillary data), along with the given metadata. The
+    /// other process can receive it with [`recv_from`](Self::recv_from) (or
+    /// [`Pty::recv_from`](crate::Pty::recv_from) or
+    /// [`blocking::Pty::recv_from`](crate::blocking::Pty::recv_from)).
+    ///
+    /// The pty remains open in this process until it is dropped, which
+    /// should typically happen once it has been sent, since both processes
+    /// reading from the same pty will result in each one seeing only part of
+    /// the output.
+    ///
+    /// # Errors
+    /// Returns an error if sending to the socket failed.
+    pub async fn send_to(
+        &self,
+        stream: &async_io::Async<std::os::unix::net::UnixStream>,
+        metadata: &crate::PtyMetadata,
+    ) -> crate::Result<()> {
+        stream
+            .write_with(|stream| {
+                crate::transfer::send(
+                    std::os::fd::AsFd::as_fd(stream),
+                    self.0.get_ref(),
+                    metadata,
+                )
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Receives a pty sent by [`send_to`](Self::send_to) (or
+    /// [`Pty::send_to`](crate::Pty::send_to) or
+    /// [`blocking::Pty::send_to`](crate::blocking::Pty::send_to)) from a
+    /// unix socket, along with the metadata it was sent with. The pty is set
+    /// up as with [`from_fd`](Self::from_fd).
+    ///
+    /// # Errors
+    /// Returns an error if receiving from the socket failed, if the data
+    /// received was not sent by `send_to`, or if setting up the pty failed.
+    pub async fn recv_from(
+        stream: &async_io::Async<std::os::unix::net::UnixStream>,
+    ) -> crate::Result<(Self, crate::PtyMetadata)> {
+        let (pty, metadata) = stream
+            .read_with(|stream| {
+                crate::transfer::recv(std::os::fd::AsFd::as_fd(stream))
+            })
+            .await?;
+        Ok((Self::from_sys(pty)?, metadata))
+    }
+
+    /// Change the terminal size associated with the pty.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to set the terminal size.
+    pub fn resize(&self, size: crate::Size) -> crate::Result<()> {
+        self.0.get_ref().set_term_size(size)
+    }
+
+    /// Returns the terminal size currently associated with the pty.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to retrieve the terminal size.
+    pub fn size(&self) -> crate::Result<crate::Size> {
+        self.0.get_ref().term_size()
+    }
+
+    /// Returns the current terminal attributes of the pty.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to retrieve the terminal
+    /// attributes.
+    pub fn termios(&self) -> crate::Result<crate::Termios> {
+        self.0.get_ref().termios()
+    }
+
+    /// Change the terminal attributes of the pty. See [`crate::SetArg`] for
+    /// the meaning of `when`.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to set the terminal attributes.
+    pub fn set_termios(
+        &self,
+        when: crate::SetArg,
+        termios: &crate::Termios,
+    ) -> crate::Result<()> {
+        self.0.get_ref().set_termios(when, termios)
+    }
+
+    /// Returns the id of the foreground process group of the pty. See
+    /// [`Pty::foreground_process_group`](crate::blocking::Pty::foreground_process_group).
+    ///
+    /// # Errors
+    /// Returns an error if the foreground process group could not be
+    /// determined.
+    pub fn foreground_process_group(&self) -> crate::Result<u32> {
+        self.0.get_ref().foreground_process_group()
+    }
+
+    /// Sends the given signal to the foreground process group of the pty
+    /// (see [`foreground_process_group`](Self::foreground_process_group)).
+    ///
+    /// # Errors
+    /// Returns an error if the foreground process group could not be
+    /// determined, or if the signal could not be sent.
+    pub fn signal_foreground(
+        &self,
+        signal: crate::Signal,
+    ) -> crate::Result<()> {
+        self.0.get_ref().signal_foreground(signal)
+    }
+
+    /// Returns the id of the session which the pty is the controlling
+    /// terminal of.
+    ///
+    /// # Errors
+    /// Returns an error if the pty is not the controlling terminal of any
+    /// session.
+    pub fn session_id(&self) -> crate::Result<u32> {
+        self.0.get_ref().session_id()
+    }
+
+    /// Enables or disables packet mode on the pty. See
+    /// [`Pty::set_packet_mode`](crate::blocking::Pty::set_packet_mode).
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to change the packet mode.
+    pub fn set_packet_mode(&self, enabled: bool) -> crate::Result<()> {
+        self.0.get_ref().set_packet_mode(enabled)
+    }
+
+    /// Sends the interrupt character (`VINTR`, usually `^C`) to the pty. See
+    /// [`Pty::send_interrupt`](crate::blocking::Pty::send_interrupt).
+    ///
+    /// # Errors
+    /// Returns an error if the terminal attributes could not be retrieved,
+    /// if the character is disabled, or if writing to the pty failed.
+    pub async fn send_interrupt(&mut self) -> crate::Result<()> {
+        send_control_char(&self.0, crate::ControlChar::Intr).await
+    }
+
+    /// Sends the quit character (`VQUIT`, usually `^\`) to the pty. See
+    /// [`Pty::send_quit`](crate::blocking::Pty::send_quit).
+    ///
+    /// # Errors
+    /// Returns an error if the terminal attributes could not be retrieved,
+    /// if the character is disabled, or if writing to the pty failed.
+    pub async fn send_quit(&mut self) -> crate::Result<()> {
+        send_control_char(&self.0, crate::ControlChar::Quit).await
+    }
+
+    /// Sends the suspend character (`VSUSP`, usually `^Z`) to the pty. See
+    /// [`Pty::send_suspend`](crate::blocking::Pty::send_suspend).
+    ///
+    /// # Errors
+    /// Returns an error if the terminal attributes could not be retrieved,
+    /// if the character is disabled, or if writing to the pty failed.
+    pub async fn send_suspend(&mut self) -> crate::Result<()> {
+        send_control_char(&self.0, crate::ControlChar::Susp).await
+    }
+
+    /// Sends the end of file character (`VEOF`, usually `^D`) to the pty.
+    /// See [`Pty::send_eof`](crate::blocking::Pty::send_eof).
+    ///
+    /// # Errors
+    /// Returns an error if the terminal attributes could not be retrieved,
+    /// if the character is disabled, or if writing to the pty failed.
+    pub async fn send_eof(&mut self) -> crate::Result<()> {
+        send_control_char(&self.0, crate::ControlChar::Eof).await
+    }
+
+    /// Sends the erase character (`VERASE`, usually `^?`) to the pty. See
+    /// [`Pty::send_erase`](crate::blocking::Pty::send_erase).
+    ///
+    /// # Errors
+    /// Returns an error if the terminal attributes could not be retrieved,
+    /// if the character is disabled, or if writing to the pty failed.
+    pub async fn send_erase(&mut self) -> crate::Result<()> {
+        send_control_char(&self.0, crate::ControlChar::Erase).await
+    }
+
+    /// Opens a file descriptor for the other end of the pty, which should be
+    /// attached to the child process running in it. See
+    /// [`Command::spawn`](crate::async_io::Command::spawn).
+    ///
+    /// # Errors
+    /// Returns an error if the device node to open could not be determined,
+    /// or if the device node could not be opened.
+    pub fn pts(&self) -> crate::Result<Pts> {
+        Ok(Pts(self.0.get_ref().pts()?))
+    }
+
+    /// Returns the index of the pty, as used in the name of its device node
+    /// (for instance, `3` for `/dev/pts/3`). This is mainly useful for
+    /// logging.
+    ///
+    /// # Errors
+    /// Returns an error if the index could not be determined.
+    pub fn pts_index(&self) -> crate::Result<u32> {
+        self.0.get_ref().pts_index()
+    }
+
+    /// Reads all remaining output from the pty until every process holding
+    /// the child end of the pty open has closed it (typically, when the
+    /// child process and any other processes in its session have exited),
+    /// and returns it.
+    ///
+    /// Note that this will never return if the [`Pts`] used to spawn the
+    /// child is still open.
+    ///
+    /// # Errors
+    /// Returns an error if reading from the pty failed.
+    pub async fn drain_until_hangup(&mut self) -> crate::Result<Vec<u8>> {
+        drain_until_hangup(&self.0).await
+    }
+
+    /// Splits a `Pty` into a read half and a write half, which can be used to
+    /// read from and write to the pty concurrently. Does not allocate, but
+    /// the returned halves cannot be moved to independent tasks.
+    ///
+    /// Note that if `futures::io::AsyncReadExt` is in scope, `pty.split()`
+    /// will call its `split` method instead, so this needs to be called as
+    /// `Pty::split(&mut pty)`.
+    pub fn split(&mut self) -> (ReadPty<'_>, WritePty<'_>) {
+        (ReadPty(&self.0), WritePty(&self.0))
+    }
+
+    /// Splits a `Pty` into a read half and a write half, which can be used to
+    /// read from and write to the pty concurrently. This method requires an
+    /// allocation, but the returned halves can be moved to independent tasks.
+    /// The original `Pty` instance can be recovered via the
+    /// [`OwnedReadPty::unsplit`] method.
+    #[must_use]
+    pub fn into_split(self) -> (OwnedReadPty, OwnedWritePty) {
+        let Self(pt) = self;
+        let read_pt = std::sync::Arc::new(pt);
+        let write_pt = std::sync::Arc::clone(&read_pt);
+        (OwnedReadPty(read_pt), OwnedWritePty(write_pt))
+    }
+}
+
+impl From<Pty> for std::os::fd::OwnedFd {
+    fn from(pty: Pty) -> Self {
+        // deregistering can only fail if the file descriptor isn't
+        // registered with the reactor, which from_sys guarantees it is
+        pty.0
+            .into_inner()
+            .expect("failed to deregister the pty from the reactor")
+            .into()
+    }
+}
+
+impl std::os::fd::AsFd for Pty {
+    fn as_fd(&self) -> std::os::fd::BorrowedFd<'_> {
+        self.0.get_ref().as_fd()
+    }
+}
+
+impl std::os::fd::AsRawFd for Pty {
+    fn as_raw_fd(&self) -> std::os::fd::RawFd {
+        self.0.get_ref().as_raw_fd()
+    }
+}
+
+impl futures_io::AsyncRead for Pty {
+    fn poll_read(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &mut [u8],
+    ) -> std::task::Poll<std::io::Result<usize>> {
+        poll_read(&self.0, cx, buf)
+    }
+}
+
+impl futures_io::AsyncWrite for Pty {
+    fn poll_write(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &[u8],
+    ) -> std::task::Poll<std::io::Result<usize>> {
+        poll_write(&self.0, cx, buf)
+    }
+
+    fn poll_flush(
+        self: std::pin::Pin<&mut Self>,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(
+        self: std::pin::Pin<&mut Self>,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+}
+
+/// The child end of the pty
+///
+/// See [`Pty::pts`] and [`Command::spawn`](crate::async_io::Command::spawn)
+pub struct Pts(pub(crate) crate::sys::Pts);
+
+impl Pts {
+    /// Returns the terminal size as seen from the child end of the pty.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to retrieve the terminal size.
+    pub fn size(&self) -> crate::Result<crate::Size> {
+        self.0.term_size()
+    }
+
+    /// Returns the path of the device node for the child end of the pty
+    /// (for instance, `/dev/pts/3`). See
+    /// [`Pts::path`](crate::blocking::Pts::path).
+    #[must_use]
+    pub fn path(&self) -> &std::path::Path {
+        self.0.path()
+    }
+}
+
+/// Borrowed read half of a [`Pty`]
+pub struct ReadPty<'a>(&'a AsyncPty);
+
+impl ReadPty<'_> {
+    /// Reads all remaining output from the pty until every process holding
+    /// the child end of the pty open has closed it, and returns it. See
+    /// [`Pty::drain_until_hangup`].
+    ///
+    /// # Errors
+    /// Returns an error if reading from the pty failed.
+    pub async fn drain_until_hangup(&mut self) -> crate::Result<Vec<u8>> {
+        drain_until_hangup(self.0).await
+    }
+}
+
+impl std::os::fd::AsFd for ReadPty<'_> {
+    fn as_fd(&self) -> std::os::fd::BorrowedFd<'_> {
+        self.0.get_ref().as_fd()
+    }
+}
+
+impl std::os::fd::AsRawFd for ReadPty<'_> {
+    fn as_raw_fd(&self) -> std::os::fd::RawFd {
+        self.0.get_ref().as_raw_fd()
+    }
+}
+
+impl futures_io::AsyncRead for ReadPty<'_> {
+    fn poll_read(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &mut [u8],
+    ) -> std::task::Poll<std::io::Result<usize>> {
+        poll_read(self.0, cx, buf)
+    }
+}
+
+/// Borrowed write half of a [`Pty`]
+pub struct WritePty<'a>(&'a AsyncPty);
+
+impl WritePty<'_> {
+    /// Change the terminal size associated with the pty.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to set the terminal size.
+    pub fn resize(&self, size: crate::Size) -> crate::Result<()> {
+        self.0.get_ref().set_term_size(size)
+    }
+
+    /// Returns the terminal size currently associated with the pty.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to retrieve the terminal size.
+    pub fn size(&self) -> crate::Result<crate::Size> {
+        self.0.get_ref().term_size()
+    }
+
+    /// Returns the current terminal attributes of the pty.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to retrieve the terminal
+    /// attributes.
+    pub fn termios(&self) -> crate::Result<crate::Termios> {
+        self.0.get_ref().termios()
+    }
+
+    /// Change the terminal attributes of the pty. See [`crate::SetArg`] for
+    /// the meaning of `when`.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to set the terminal attributes.
+    pub fn set_termios(
+        &self,
+        when: crate::SetArg,
+        termios: &crate::Termios,
+    ) -> crate::Result<()> {
+        self.0.get_ref().set_termios(when, termios)
+    }
+
+    /// Returns the id of the foreground process group of the pty. See
+    /// [`Pty::foreground_process_group`].
+    ///
+    /// # Errors
+    /// Returns an error if the foreground process group could not be
+    /// determined.
+    pub fn foreground_process_group(&self) -> crate::Result<u32> {
+        self.0.get_ref().foreground_process_group()
+    }
+
+    /// Sends the given signal to the foreground process group of the pty
+    /// (see [`foreground_process_group`](Self::foreground_process_group)).
+    ///
+    /// # Errors
+    /// Returns an error if the foreground process group could not be
+    /// determined, or if the signal could not be sent.
+    pub fn signal_foreground(
+        &self,
+        signal: crate::Signal,
+    ) -> crate::Result<()> {
+        self.0.get_ref().signal_foreground(signal)
+    }
+
+    /// Returns the id of the session which the pty is the controlling
+    /// terminal of.
+    ///
+    /// # Errors
+    /// Returns an error if the pty is not the controlling terminal of any
+    /// session.
+    pub fn session_id(&self) -> crate::Result<u32> {
+        self.0.get_ref().session_id()
+    }
+
+    /// Enables or disables packet mode on the pty. See
+    /// [`Pty::set_packet_mode`].
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to change the packet mode.
+    pub fn set_packet_mode(&self, enabled: bool) -> crate::Result<()> {
+        self.0.get_ref().set_packet_mode(enabled)
+    }
+
+    /// Sends the interrupt character (`VINTR`, usually `^C`) to the pty. See
+    /// [`Pty::send_interrupt`].
+    ///
+    /// # Errors
+    /// Returns an error if the terminal attributes could not be retrieved,
+    /// if the character is disabled, or if writing to the pty failed.
+    pub async fn send_interrupt(&mut self) -> crate::Result<()> {
+        send_control_char(self.0, crate::ControlChar::Intr).await
+    }
+
+    /// Sends the quit character (`VQUIT`, usually `^\`) to the pty. See
+    /// [`Pty::send_quit`].
+    ///
+    /// # Errors
+    /// Returns an error if the terminal attributes could not be retrieved,
+    /// if the character is disabled, or if writing to the pty failed.
+    pub async fn send_quit(&mut self) -> crate::Result<()> {
+        send_control_char(self.0, crate::ControlChar::Quit).await
+    }
+
+    /// Sends the suspend character (`VSUSP`, usually `^Z`) to the pty. See
+    /// [`Pty::send_suspend`].
+    ///
+    /// # Errors
+    /// Returns an error if the terminal attributes could not be retrieved,
+    /// if the character is disabled, or if writing to the pty failed.
+    pub async fn send_suspend(&mut self) -> crate::Result<()> {
+        send_control_char(self.0, crate::ControlChar::Susp).await
+    }
+
+    /// Sends the end of file character (`VEOF`, usually `^D`) to the pty.
+    /// See [`Pty::send_eof`].
+    ///
+    /// # Errors
+    /// Returns an error if the terminal attributes could not be retrieved,
+    /// if the character is disabled, or if writing to the pty failed.
+    pub async fn send_eof(&mut self) -> crate::Result<()> {
+        send_control_char(self.0, crate::ControlChar::Eof).await
+    }
+
+    /// Sends the erase character (`VERASE`, usually `^?`) to the pty. See
+    /// [`Pty::send_erase`].
+    ///
+    /// # Errors
+    /// Returns an error if the terminal attributes could not be retrieved,
+    /// if the character is disabled, or if writing to the pty failed.
+    pub async fn send_erase(&mut self) -> crate::Result<()> {
+        send_control_char(self.0, crate::ControlChar::Erase).await
+    }
+}
+
+impl futures_io::AsyncWrite for WritePty<'_> {
+    fn poll_write(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &[u8],
+    ) -> std::task::Poll<std::io::Result<usize>> {
+        poll_write(self.0, cx, buf)
+    }
+
+    fn poll_flush(
+        self: std::pin::Pin<&mut Self>,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(
+        self: std::pin::Pin<&mut Self>,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+}
+
+/// Owned read half of a [`Pty`]
+#[derive(Debug)]
+pub struct OwnedReadPty(std::sync::Arc<AsyncPty>);
+
+impl OwnedReadPty {
+    /// Reads all remaining output from the pty until every process holding
+    /// the child end of the pty open has closed it, and returns it. See
+    /// [`Pty::drain_until_hangup`].
+    ///
+    /// # Errors
+    /// Returns an error if reading from the pty failed.
+    pub async fn drain_until_hangup(&mut self) -> crate::Result<Vec<u8>> {
+        drain_until_hangup(&self.0).await
+    }
+
+    /// Attempt to join the two halves of a `Pty` back into a single instance.
+    /// The two halves must have originated from calling
+    /// [`into_split`](Pty::into_split) on a single instance.
+    ///
+    /// # Errors
+    /// Returns an error if the two halves came from different [`Pty`]
+    /// instances. The mismatched halves are returned as part of the error.
+    pub fn unsplit(self, write_half: OwnedWritePty) -> crate::Result<Pty> {
+        let Self(read_pt) = self;
+        let OwnedWritePty(write_pt) = write_half;
+        if std::sync::Arc::ptr_eq(&read_pt, &write_pt) {
+            drop(write_pt);
+            Ok(Pty(std::sync::Arc::try_unwrap(read_pt)
+                // it shouldn't be possible for more than two references to
+                // the same pty to exist
+                .unwrap_or_else(|_| unreachable!())))
+        } else {
+            Err(crate::Error::AsyncIoUnsplit(
+                Self(read_pt),
+                OwnedWritePty(write_pt),
+            ))
+        }
+    }
+}
+
+impl std::os::fd::AsFd for OwnedReadPty {
+    fn as_fd(&self) -> std::os::fd::BorrowedFd<'_> {
+        self.0.get_ref().as_fd()
+    }
+}
+
+impl std::os::fd::AsRawFd for OwnedReadPty {
+    fn as_raw_fd(&self) -> std::os::fd::RawFd {
+        self.0.get_ref().as_raw_fd()
+    }
+}
+
+impl futures_io::AsyncRead for OwnedReadPty {
+    fn poll_read(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &mut [u8],
+    ) -> std::task::Poll<std::io::Result<usize>> {
+        poll_read(&self.0, cx, buf)
+    }
+}
+
+/// Owned write half of a [`Pty`]
+#[derive(Debug)]
+pub struct OwnedWritePty(std::sync::Arc<AsyncPty>);
+
+impl OwnedWritePty {
+    /// Change the terminal size associated with the pty.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to set the terminal size.
+    pub fn resize(&self, size: crate::Size) -> crate::Result<()> {
+        self.0.get_ref().set_term_size(size)
+    }
+
+    /// Returns the terminal size currently associated with the pty.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to retrieve the terminal size.
+    pub fn size(&self) -> crate::Result<crate::Size> {
+        self.0.get_ref().term_size()
+    }
+
+    /// Returns the current terminal attributes of the pty.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to retrieve the terminal
+    /// attributes.
+    pub fn termios(&self) -> crate::Result<crate::Termios> {
+        self.0.get_ref().termios()
+    }
+
+    /// Change the terminal attributes of the pty. See [`crate::SetArg`] for
+    /// the meaning of `when`.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to set the terminal attributes.
+    pub fn set_termios(
+        &self,
+        when: crate::SetArg,
+        termios: &crate::Termios,
+    ) -> crate::Result<()> {
+        self.0.get_ref().set_termios(when, termios)
+    }
+
+    /// Returns the id of the foreground process group of the pty. See
+    /// [`Pty::foreground_process_group`].
+    ///
+    /// # Errors
+    /// Returns an error if the foreground process group could not be
+    /// determined.
+    pub fn foreground_process_group(&self) -> crate::Result<u32> {
+        self.0.get_ref().foreground_process_group()
+    }
+
+    /// Sends the given signal to the foreground process group of the pty
+    /// (see [`foreground_process_group`](Self::foreground_process_group)).
+    ///
+    /// # Errors
+    /// Returns an error if the foreground process group could not be
+    /// determined, or if the signal could not be sent.
+    pub fn signal_foreground(
+        &self,
+        signal: crate::Signal,
+    ) -> crate::Result<()> {
+        self.0.get_ref().signal_foreground(signal)
+    }
+
+    /// Returns the id of the session which the pty is the controlling
+    /// terminal of.
+    ///
+    /// # Errors
+    /// Returns an error if the pty is not the controlling terminal of any
+    /// session.
+    pub fn session_id(&self) -> crate::Result<u32> {
+        self.0.get_ref().session_id()
+    }
+
+    /// Enables or disables packet mode on the pty. See
+    /// [`Pty::set_packet_mode`].
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to change the packet mode.
+    pub fn set_packet_mode(&self, enabled: bool) -> crate::Result<()> {
+        self.0.get_ref().set_packet_mode(enabled)
+    }
+
+    /// Sends the interrupt character (`VINTR`, usually `^C`) to the pty. See
+    /// [`Pty::send_interrupt`].
+    ///
+    /// # Errors
+    /// Returns an error if the terminal attributes could not be retrieved,
+    /// if the character is disabled, or if writing to the pty failed.
+    pub async fn send_interrupt(&mut self) -> crate::Result<()> {
+        send_control_char(&self.0, crate::ControlChar::Intr).await
+    }
+
+    /// Sends the quit character (`VQUIT`, usually `^\`) to the pty. See
+    /// [`Pty::send_quit`].
+    ///
+    /// # Errors
+    /// Returns an error if the terminal attributes could not be retrieved,
+    /// if the character is disabled, or if writing to the pty failed.
+    pub async fn send_quit(&mut self) -> crate::Result<()> {
+        send_control_char(&self.0, crate::ControlChar::Quit).await
+    }
+
+    /// Sends the suspend character (`VSUSP`, usually `^Z`) to the pty. See
+    /// [`Pty::send_suspend`].
+    ///
+    /// # Errors
+    /// Returns an error if the terminal attributes could not be retrieved,
+    /// if the character is disabled, or if writing to the pty failed.
+    pub async fn send_suspend(&mut self) -> crate::Result<()> {
+        send_control_char(&self.0, crate::ControlChar::Susp).await
+    }
+
+    /// Sends the end of file character (`VEOF`, usually `^D`) to the pty.
+    /// See [`Pty::send_eof`].
+    ///
+    /// # Errors
+    /// Returns an error if the terminal attributes could not be retrieved,
+    /// if the character is disabled, or if writing to the pty failed.
+    pub async fn send_eof(&mut self) -> crate::Result<()> {
+        send_control_char(&self.0, crate::ControlChar::Eof).await
+    }
+
+    /// Sends the erase character (`VERASE`, usually `^?`) to the pty. See
+    /// [`Pty::send_erase`].
+    ///
+    /// # Errors
+    /// Returns an error if the terminal attributes could not be retrieved,
+    /// if the character is disabled, or if writing to the pty failed.
+    pub async fn send_erase(&mut self) -> crate::Result<()> {
+        send_control_char(&self.0, crate::ControlChar::Erase).await
+    }
+}
+
+impl std::os::fd::AsFd for OwnedWritePty {
+    fn as_fd(&self) -> std::os::fd::BorrowedFd<'_> {
+        self.0.get_ref().as_fd()
+    }
+}
+
+impl std::os::fd::AsRawFd for OwnedWritePty {
+    fn as_raw_fd(&self) -> std::os::fd::RawFd {
+        self.0.get_ref().as_raw_fd()
+    }
+}
+
+impl futures_io::AsyncWrite for OwnedWritePty {
+    fn poll_write(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &[u8],
+    ) -> std::task::Poll<std::io::Result<usize>> {
+        poll_write(&self.0, cx, buf)
+    }
+
+    fn poll_flush(
+        self: std::pin::Pin<&mut Self>,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(
+        self: std::pin::Pin<&mut Self>,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+}
+
+fn poll_read(
+    pty: &AsyncPty,
+    cx: &mut std::task::Context<'_>,
+    buf: &mut [u8],
+) -> std::task::Poll<std::io::Result<usize>> {
+    // the reactor only wakes us up on new readiness events, so we have to
+    // try the read first rather than waiting to become readable
+    loop {
+        match pty.get_ref().read(buf) {
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {}
+            res => return std::task::Poll::Ready(res),
+        }
+        match pty.poll_readable(cx) {
+            std::task::Poll::Ready(res) => res?,
+            std::task::Poll::Pending => return std::task::Poll::Pending,
+        }
+    }
+}
+
+fn poll_write(
+    pty: &AsyncPty,
+    cx: &mut std::task::Context<'_>,
+    buf: &[u8],
+) -> std::task::Poll<std::io::Result<usize>> {
+    loop {
+        match pty.get_ref().write(buf) {
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {}
+            res => return std::task::Poll::Ready(res),
+        }
+        match pty.poll_writable(cx) {
+            std::task::Poll::Ready(res) => res?,
+            std::task::Poll::Pending => return std::task::Poll::Pending,
+        }
+    }
+}
+
+async fn send_control_char(
+    pty: &AsyncPty,
+    cc: crate::ControlChar,
+) -> crate::Result<()> {
+    let byte = pty.get_ref().control_char(cc)?;
+    let n = std::future::poll_fn(|cx| poll_write(pty, cx, &[byte])).await?;
+    if n == 0 {
+        return Err(
+            std::io::Error::from(std::io::ErrorKind::WriteZero).into()
+        );
+    }
+    Ok(())
+}
+
+async fn drain_until_hangup(pty: &AsyncPty) -> crate::Result<Vec<u8>> {
+    let mut output = vec![];
+    let mut buf = [0; 4096];
+    loop {
+        let n =
+            std::future::poll_fn(|cx| poll_read(pty, cx, &mut buf)).await?;
+        if n == 0 {
+            return Ok(output);
+        }
+        output.extend_from_slice(&buf[..n]);
+    }
+}
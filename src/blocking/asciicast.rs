@@ -0,0 +1,202 @@
+//! Blocking equivalent of [`pty_process::asciicast`](crate::asciicast)
+
+use std::io::Write as _;
+
+pub use crate::asciicast::{Event, Header, Recorder, Speed};
+
+/// Wrapper around a [`Pty`](crate::blocking::Pty) which records the session
+/// in the asciicast v2 format.
+///
+/// All data read from the pty is recorded as output. Data written to the
+/// pty is recorded as input if enabled via
+/// [`set_record_input`](Self::set_record_input), and calls to
+/// [`resize`](Self::resize) are recorded as resize events.
+pub struct Recording<W: std::io::Write> {
+    pty: crate::blocking::Pty,
+    recorder: Recorder<W>,
+    record_input: bool,
+}
+
+impl<W: std::io::Write> Recording<W> {
+    /// Starts recording the given pty to `writer`. The size in the header is
+    /// taken from the current size of the pty.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to retrieve the terminal size, or
+    /// if writing the header failed.
+    pub fn new(pty: crate::blocking::Pty, writer: W) -> crate::Result<Self> {
+        let recorder = Recorder::new(writer, pty.size()?)?;
+        Ok(Self {
+            pty,
+            recorder,
+            record_input: false,
+        })
+    }
+
+    /// Sets whether data written to the pty should be recorded as input
+    /// events. Defaults to `false`.
+    pub fn set_record_input(&mut self, record_input: bool) {
+        self.record_input = record_input;
+    }
+
+    /// Change the terminal size associated with the pty, and record the
+    /// resize event.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to set the terminal size, or if
+    /// writing the event failed.
+    pub fn resize(&mut self, size: crate::Size) -> crate::Result<()> {
+        self.pty.resize(size)?;
+        self.recorder.record_resize(size)?;
+        Ok(())
+    }
+
+    /// Returns a mutable reference to the underlying recorder, for instance
+    /// to add markers.
+    pub fn recorder(&mut self) -> &mut Recorder<W> {
+        &mut self.recorder
+    }
+
+    /// Returns a reference to the underlying pty.
+    #[must_use]
+    pub fn get_ref(&self) -> &crate::blocking::Pty {
+        &self.pty
+    }
+
+    /// Stops recording, returning the underlying pty and writer.
+    pub fn into_inner(self) -> (crate::blocking::Pty, W) {
+        (self.pty, self.recorder.into_inner())
+    }
+}
+
+impl<W: std::io::Write> std::io::Read for Recording<W> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let bytes = self.pty.read(buf)?;
+        self.recorder.record_output(&buf[..bytes])?;
+        Ok(bytes)
+    }
+}
+
+impl<W: std::io::Write> std::io::Write for Recording<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let bytes = self.pty.write(buf)?;
+        if self.record_input {
+            self.recorder.record_input(&buf[..bytes])?;
+        }
+        Ok(bytes)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.pty.flush()
+    }
+}
+
+/// Reads events from a stream in the asciicast v2 format.
+pub struct Player<R: std::io::BufRead> {
+    reader: R,
+    header: Header,
+}
+
+impl<R: std::io::BufRead> Player<R> {
+    /// Creates a new player, reading the asciicast header from `reader`.
+    ///
+    /// # Errors
+    /// Returns an error if reading from `reader` failed, or if the header
+    /// is not a valid asciicast v2 header.
+    pub fn new(mut reader: R) -> std::io::Result<Self> {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let header = crate::asciicast::parse_header(&line)?;
+        Ok(Self { reader, header })
+    }
+
+    /// Returns the header of the recording.
+    #[must_use]
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Reads the next event from the recording, along with its time relative
+    /// to the start of the recording. Returns `None` at the end of the
+    /// stream.
+    ///
+    /// # Errors
+    /// Returns an error if reading from the underlying reader failed, or if
+    /// the event could not be parsed.
+    pub fn next_event(
+        &mut self,
+    ) -> std::io::Result<Option<(std::time::Duration, Event)>> {
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if self.reader.read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+            if !line.trim().is_empty() {
+                break;
+            }
+        }
+        crate::asciicast::parse_event(&line).map(Some)
+    }
+
+    /// Replays the output events of the recording into `writer`, at the
+    /// given speed. Other events are ignored.
+    ///
+    /// # Errors
+    /// Returns an error if reading the recording or writing to `writer`
+    /// failed, or if `speed` is invalid.
+    pub fn play<W: std::io::Write>(
+        &mut self,
+        mut writer: W,
+        speed: Speed,
+    ) -> std::io::Result<()> {
+        let start = std::time::Instant::now();
+        while let Some((time, event)) = self.next_event()? {
+            sleep_until(start + speed.scale(time)?);
+            if let Event::Output(data) = event {
+                writer.write_all(&data)?;
+                writer.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Replays the recording into the given pty, at the given speed. Output
+    /// events are written to the pty and resize events resize it.
+    ///
+    /// # Errors
+    /// Returns an error if reading the recording, writing to the pty, or
+    /// resizing the pty failed, or if `speed` is invalid.
+    pub fn play_pty(
+        &mut self,
+        mut pty: &crate::blocking::Pty,
+        speed: Speed,
+    ) -> crate::Result<()> {
+        pty.resize(self.header.size())?;
+        let start = std::time::Instant::now();
+        while let Some((time, event)) = self.next_event()? {
+            sleep_until(start + speed.scale(time)?);
+            match event {
+                Event::Output(data) => pty.write_all(&data)?,
+                Event::Resize(size) => pty.resize(size)?,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<R: std::io::BufRead> Iterator for Player<R> {
+    type Item = std::io::Result<(std::time::Duration, Event)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_event().transpose()
+    }
+}
+
+fn sleep_until(deadline: std::time::Instant) {
+    let now = std::time::Instant::now();
+    if deadline > now {
+        std::thread::sleep(deadline - now);
+    }
+}
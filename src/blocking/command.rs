@@ -166,6 +166,40 @@ impl Command {
         Ok(self.inner.spawn()?)
     }
 
+    /// Allocates a new pty with the given size, and executes the command as
+    /// a child process on it, as with [`spawn`](Self::spawn). The returned
+    /// [`PtyChild`](crate::blocking::PtyChild) owns both the pty and the
+    /// child process, and the child end of the pty is closed in the parent
+    /// once the child has been spawned.
+    ///
+    /// # Errors
+    /// Returns an error if the pty could not be allocated or resized, or if
+    /// spawning the child failed (see [`spawn`](Self::spawn)).
+    pub fn spawn_pty(
+        &mut self,
+        size: crate::Size,
+    ) -> crate::Result<crate::blocking::PtyChild> {
+        let pty = crate::blocking::Pty::builder().size(size).build()?;
+        let child = self.spawn(&pty.pts()?);
+        self.release_pts();
+        Ok(crate::blocking::PtyChild::new(pty, child?))
+    }
+
+    // spawn stores duplicates of the child end of the pty as the stdio of
+    // the underlying command, which would otherwise keep the pty from
+    // hanging up for as long as this command is alive
+    fn release_pts(&mut self) {
+        if !self.stdin {
+            self.inner.stdin(std::process::Stdio::null());
+        }
+        if !self.stdout {
+            self.inner.stdout(std::process::Stdio::null());
+        }
+        if !self.stderr {
+            self.inner.stderr(std::process::Stdio::null());
+        }
+    }
+
     /// See [`std::os::unix::process::CommandExt::uid`]
     pub fn uid(&mut self, id: u32) -> &mut Self {
         self.inner.uid(id);
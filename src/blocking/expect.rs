@@ -0,0 +1,181 @@
+//! Blocking equivalent of [`pty_process::expect`](crate::expect)
+
+use std::io::{Read as _, Write as _};
+
+/// Wrapper around a [`Pty`](crate::blocking::Pty) which allows waiting for
+/// specific output from the child process.
+pub struct Session {
+    pty: crate::blocking::Pty,
+    buf: crate::expect::Buffer,
+}
+
+impl Session {
+    /// Creates a new session reading from and writing to the given pty.
+    #[must_use]
+    pub fn new(pty: crate::blocking::Pty) -> Self {
+        Self {
+            pty,
+            buf: crate::expect::Buffer::default(),
+        }
+    }
+
+    /// Waits until the output of the child process matches `pattern`. On
+    /// success, [`before`](Self::before) will contain the output preceding
+    /// the match and [`after`](Self::after) will contain the matched output
+    /// itself.
+    ///
+    /// # Errors
+    /// Returns [`Error::Timeout`](crate::Error::Timeout) if the pattern was
+    /// not matched before `timeout` elapsed, an error of kind
+    /// [`UnexpectedEof`](std::io::ErrorKind::UnexpectedEof) if the pty was
+    /// closed before the pattern was matched, or an error if reading from
+    /// the pty failed.
+    pub fn expect(
+        &mut self,
+        pattern: impl Into<crate::expect::Pattern>,
+        timeout: std::time::Duration,
+    ) -> crate::Result<()> {
+        self.expect_any([pattern], timeout).map(|_| ())
+    }
+
+    /// Waits until the output of the child process matches any of
+    /// `patterns`, and returns the index of the pattern which matched. If
+    /// several patterns match, the one whose match starts earliest in the
+    /// output is chosen.
+    ///
+    /// # Errors
+    /// See [`expect`](Self::expect).
+    pub fn expect_any<I, P>(
+        &mut self,
+        patterns: I,
+        timeout: std::time::Duration,
+    ) -> crate::Result<usize>
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<crate::expect::Pattern>,
+    {
+        let patterns: Vec<_> = patterns.into_iter().map(Into::into).collect();
+        let deadline = std::time::Instant::now() + timeout;
+        let mut buf = [0_u8; 4096];
+        loop {
+            if let Some(i) = self.buf.try_match(&patterns) {
+                return Ok(i);
+            }
+            if self.buf.is_eof() {
+                return Err(std::io::Error::from(
+                    std::io::ErrorKind::UnexpectedEof,
+                )
+                .into());
+            }
+            if !self.wait_readable(deadline)? {
+                return Err(crate::Error::Timeout);
+            }
+            match self.pty.read(&mut buf) {
+                Ok(0) => self.buf.set_eof(),
+                Ok(bytes) => self.buf.push(&buf[..bytes]),
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    /// Waits until the child process closes the pty. Note that this can
+    /// only happen once every handle to the child end of the pty (including
+    /// the `Pts` used to spawn the child) has been closed.
+    ///
+    /// # Errors
+    /// See [`expect`](Self::expect).
+    pub fn expect_eof(
+        &mut self,
+        timeout: std::time::Duration,
+    ) -> crate::Result<()> {
+        self.expect(crate::expect::Pattern::Eof, timeout)
+    }
+
+    /// Sends the given data to the child process.
+    ///
+    /// # Errors
+    /// Returns an error if writing to the pty failed.
+    pub fn send(&mut self, data: impl AsRef<[u8]>) -> crate::Result<()> {
+        self.pty.write_all(data.as_ref())?;
+        Ok(())
+    }
+
+    /// Sends the given line, followed by a newline, to the child process.
+    ///
+    /// # Errors
+    /// Returns an error if writing to the pty failed.
+    pub fn send_line(&mut self, line: impl AsRef<[u8]>) -> crate::Result<()> {
+        self.send(line)?;
+        self.send(b"\n")
+    }
+
+    /// Sends the byte corresponding to pressing the given character while
+    /// holding the control key (so `send_control('c')` sends `^C`).
+    ///
+    /// # Errors
+    /// Returns an error if `c` does not correspond to a control character,
+    /// or if writing to the pty failed.
+    pub fn send_control(&mut self, c: char) -> crate::Result<()> {
+        let byte = crate::expect::control_byte(c)?;
+        self.send([byte])
+    }
+
+    /// Returns the output preceding the most recent match.
+    #[must_use]
+    pub fn before(&self) -> &[u8] {
+        self.buf.before()
+    }
+
+    /// Returns the output which was matched by the most recent match.
+    #[must_use]
+    pub fn after(&self) -> &[u8] {
+        self.buf.after()
+    }
+
+    /// Returns the output which has been read from the pty but not yet
+    /// consumed by a match.
+    #[must_use]
+    pub fn buffer(&self) -> &[u8] {
+        self.buf.data()
+    }
+
+    /// Returns a reference to the underlying pty.
+    #[must_use]
+    pub fn get_ref(&self) -> &crate::blocking::Pty {
+        &self.pty
+    }
+
+    /// Returns a mutable reference to the underlying pty. Reading from the
+    /// pty directly will bypass the session's buffering.
+    pub fn get_mut(&mut self) -> &mut crate::blocking::Pty {
+        &mut self.pty
+    }
+
+    /// Returns the underlying pty, discarding any buffered output.
+    #[must_use]
+    pub fn into_inner(self) -> crate::blocking::Pty {
+        self.pty
+    }
+
+    fn wait_readable(
+        &self,
+        deadline: std::time::Instant,
+    ) -> crate::Result<bool> {
+        loop {
+            let remaining =
+                deadline.saturating_duration_since(std::time::Instant::now());
+            let timeout =
+                i32::try_from(remaining.as_millis()).unwrap_or(i32::MAX);
+            let mut fds = [rustix::event::PollFd::new(
+                &self.pty,
+                rustix::event::PollFlags::IN,
+            )];
+            match rustix::event::poll(&mut fds, timeout) {
+                Ok(0) => return Ok(false),
+                Ok(_) => return Ok(true),
+                Err(rustix::io::Errno::INTR) => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use std::io::{Read as _, Write as _};
+
+/// How often to check whether the child process has exited.
+const WAIT_INTERVAL: std::time::Duration =
+    std::time::Duration::from_millis(100);
+
+/// Connects the current process's terminal to the given pty until the child
+/// process exits, and returns its exit status.
+///
+/// While this function is running, stdin is put into raw mode and all input
+/// is forwarded to the pty, and all output from the pty is written to
+/// stdout. The pty is initially resized to match the size of the terminal
+/// attached to stdin (if any). Once the child exits, any remaining output
+/// is written to stdout, and the original terminal settings are restored
+/// (this also happens if this function returns early or panics).
+///
+/// Note that the `Pts` used to spawn the child should be dropped before
+/// calling this function, since otherwise the pty will not be closed when
+/// the child exits.
+///
+/// # Errors
+/// Returns an error if the terminal settings of stdin could not be changed,
+/// if reading from or writing to stdin, stdout, or the pty failed, or if
+/// waiting for the child process failed.
+pub fn interact(
+    pty: &mut crate::blocking::Pty,
+    child: &mut std::process::Child,
+) -> crate::Result<std::process::ExitStatus> {
+    if let Some(size) = crate::sys::host_term_size() {
+        pty.resize(size)?;
+    }
+    let _raw = crate::sys::RawGuard::new()?;
+
+    let stdin = rustix::stdio::stdin();
+    let mut stdout = std::io::stdout().lock();
+    let mut buf = [0_u8; 4096];
+    let mut stdin_open = true;
+    let timeout =
+        i32::try_from(WAIT_INTERVAL.as_millis()).unwrap_or(i32::MAX);
+
+    loop {
+        if let Some(status) = child.try_wait()? {
+            drain(pty, &mut stdout, &mut buf)?;
+            return Ok(status);
+        }
+
+        let mut fds = vec![rustix::event::PollFd::new(
+            &*pty,
+            rustix::event::PollFlags::IN,
+        )];
+        if stdin_open {
+            fds.push(rustix::event::PollFd::new(
+                &stdin,
+                rustix::event::PollFlags::IN,
+            ));
+        }
+        match rustix::event::poll(&mut fds, timeout) {
+            Ok(_) => {}
+            Err(rustix::io::Errno::INTR) => continue,
+            Err(e) => return Err(e.into()),
+        }
+        let pty_ready = !fds[0].revents().is_empty();
+        let stdin_ready = stdin_open && !fds[1].revents().is_empty();
+        drop(fds);
+
+        if stdin_ready {
+            match rustix::io::read(stdin, &mut buf) {
+                Ok(0) => stdin_open = false,
+                Ok(bytes) => pty.write_all(&buf[..bytes])?,
+                Err(rustix::io::Errno::INTR) => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        if pty_ready {
+            match pty.read(&mut buf) {
+                Ok(0) => break,
+                Ok(bytes) => {
+                    stdout.write_all(&buf[..bytes])?;
+                    stdout.flush()?;
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    Ok(child.wait()?)
+}
+
+fn drain(
+    pty: &mut crate::blocking::Pty,
+    stdout: &mut impl std::io::Write,
+    buf: &mut [u8],
+) -> crate::Result<()> {
+    let timeout =
+        i32::try_from(WAIT_INTERVAL.as_millis()).unwrap_or(i32::MAX);
+    loop {
+        let mut fds = [rustix::event::PollFd::new(
+            &*pty,
+            rustix::event::PollFlags::IN,
+        )];
+        match rustix::event::poll(&mut fds, timeout) {
+            Ok(0) => return Ok(()),
+            Ok(_) => {}
+            Err(rustix::io::Errno::INTR) => continue,
+            Err(e) => return Err(e.into()),
+        }
+        match pty.read(buf) {
+            Ok(0) => return Ok(()),
+            Ok(bytes) => {
+                stdout.write_all(&buf[..bytes])?;
+                stdout.flush()?;
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
@@ -1,7 +1,25 @@
 //! Blocking equivalents for [`pty_process::Command`](crate::Command) and
 //! [`pty_process::Pty`](crate::Pty)
 
+mod builder;
+pub use builder::PtyBuilder;
+mod child;
+pub use child::PtyChild;
 mod command;
 pub use command::Command;
+mod interact;
+pub use interact::interact;
+pub mod packet;
 mod pty;
 pub use pty::{Pts, Pty};
+mod resize;
+pub use resize::ResizeForwarder;
+
+#[cfg(feature = "asciicast")]
+pub mod asciicast;
+#[cfg(feature = "expect")]
+pub mod expect;
+#[cfg(feature = "screen")]
+pub mod screen;
+#[cfg(feature = "script")]
+pub mod script;
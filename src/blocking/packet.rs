@@ -0,0 +1,63 @@
+//! Blocking equivalent of [`pty_process::packet`](crate::packet)
+
+pub use crate::packet::{InputMode, Packet, PacketEvent};
+
+/// Wrapper around a [`Pty`](crate::blocking::Pty) in packet mode which
+/// decodes the data read from it into [`Packet`]s.
+///
+/// The wrapped reader must pass reads through to the pty directly (in
+/// particular, it must not be buffered), since each read from the pty
+/// returns exactly one packet.
+pub struct PacketReader<R> {
+    reader: R,
+    buf: Vec<u8>,
+    modes: crate::packet::ModeTracker,
+}
+
+impl<R: std::io::Read + std::os::fd::AsFd> PacketReader<R> {
+    /// Creates a new packet reader wrapping the given pty. Packet mode must
+    /// be enabled on the pty separately.
+    pub fn new(reader: R) -> Self {
+        let modes = crate::packet::ModeTracker::new(reader.as_fd());
+        Self {
+            reader,
+            buf: vec![0; crate::packet::BUFFER_SIZE],
+            modes,
+        }
+    }
+
+    /// Reads the next packet from the pty, or returns `None` once the pty
+    /// has been closed by every process holding the child end of it open.
+    ///
+    /// # Errors
+    /// Returns an error if reading from the pty failed.
+    pub fn read_packet(&mut self) -> crate::Result<Option<Packet>> {
+        if let Some(packet) = self.modes.pending() {
+            return Ok(Some(packet));
+        }
+        let bytes = self.reader.read(&mut self.buf)?;
+        let packet = Packet::decode(&self.buf[..bytes]);
+        if let Some(packet) = &packet {
+            self.modes.update(self.reader.as_fd(), packet);
+        }
+        Ok(packet)
+    }
+
+    /// Returns a reference to the wrapped pty.
+    #[must_use]
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Returns a mutable reference to the wrapped pty. Reading from it
+    /// directly will return the raw packets, including the status byte.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Returns the wrapped pty.
+    #[must_use]
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
@@ -1,5 +1,7 @@
+use std::io::Write as _;
+
 /// An allocated pty
-pub struct Pty(crate::sys::Pty);
+pub struct Pty(pub(crate) crate::sys::Pty);
 
 impl Pty {
     /// Allocate and return a new pty.
@@ -10,6 +12,27 @@ impl Pty {
         Ok(Self(crate::sys::Pty::open()?))
     }
 
+    /// Allocate and return a new pty from the given `ptmx` device node, such
+    /// as the `ptmx` node of a devpts instance mounted with `newinstance`
+    /// inside a container. The child end of the pty is opened from the
+    /// same devpts instance.
+    ///
+    /// # Errors
+    /// Returns an error if the device node could not be opened, or if it
+    /// is not a `ptmx` device.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn open_at(ptmx: impl AsRef<std::path::Path>) -> crate::Result<Self> {
+        Ok(Self(crate::sys::Pty::open_at(ptmx.as_ref())?))
+    }
+
+    /// Returns a builder which can be used to configure the size and
+    /// terminal attributes of a new pty before it is allocated. See
+    /// [`PtyBuilder`](crate::blocking::PtyBuilder).
+    #[must_use]
+    pub fn builder() -> crate::blocking::PtyBuilder {
+        crate::blocking::PtyBuilder::new()
+    }
+
     /// Change the terminal size associated with the pty.
     ///
     /// # Errors
@@ -18,6 +41,237 @@ impl Pty {
         self.0.set_term_size(size)
     }
 
+    /// Returns the terminal size currently associated with the pty.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to retrieve the terminal size.
+    pub fn size(&self) -> crate::Result<crate::Size> {
+        self.0.term_size()
+    }
+
+    /// Returns the current terminal attributes of the pty.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to retrieve the terminal
+    /// attributes.
+    pub fn termios(&self) -> crate::Result<crate::Termios> {
+        self.0.termios()
+    }
+
+    /// Change the terminal attributes of the pty. See [`crate::SetArg`] for
+    /// the meaning of `when`.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to set the terminal attributes.
+    pub fn set_termios(
+        &self,
+        when: crate::SetArg,
+        termios: &crate::Termios,
+    ) -> crate::Result<()> {
+        self.0.set_termios(when, termios)
+    }
+
+    /// Returns the id of the foreground process group of the pty. This is
+    /// the process group which receives signals generated by the terminal
+    /// (such as `SIGINT` when `^C` is typed), and may be different from the
+    /// process group of the child process if the child is a shell which has
+    /// started a job.
+    ///
+    /// # Errors
+    /// Returns an error if the foreground process group could not be
+    /// determined.
+    pub fn foreground_process_group(&self) -> crate::Result<u32> {
+        self.0.foreground_process_group()
+    }
+
+    /// Sends the given signal to the foreground process group of the pty
+    /// (see [`foreground_process_group`](Self::foreground_process_group)).
+    ///
+    /// # Errors
+    /// Returns an error if the foreground process group could not be
+    /// determined, or if the signal could not be sent.
+    pub fn signal_foreground(
+        &self,
+        signal: crate::Signal,
+    ) -> crate::Result<()> {
+        self.0.signal_foreground(signal)
+    }
+
+    /// Returns the id of the session which the pty is the controlling
+    /// terminal of.
+    ///
+    /// # Errors
+    /// Returns an error if the pty is not the controlling terminal of any
+    /// session.
+    pub fn session_id(&self) -> crate::Result<u32> {
+        self.0.session_id()
+    }
+
+    /// Enables or disables packet mode on the pty. In packet mode, every
+    /// read from the pty returns a leading status byte, which reports
+    /// events such as the child flushing the terminal's queues or changing
+    /// its flow control settings. Use a
+    /// [`PacketReader`](crate::blocking::packet::PacketReader) to decode the
+    /// data read from a pty in packet mode.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to change the packet mode.
+    pub fn set_packet_mode(&self, enabled: bool) -> crate::Result<()> {
+        self.0.set_packet_mode(enabled)
+    }
+
+    /// Sends the interrupt character (`VINTR`, usually `^C`) to the pty,
+    /// which will send `SIGINT` to the foreground process group if
+    /// `ISIG` is enabled. The character is looked up in the current terminal
+    /// attributes of the pty, so this works even if it has been remapped.
+    ///
+    /// # Errors
+    /// Returns an error if the terminal attributes could not be retrieved,
+    /// if the character is disabled, or if writing to the pty failed.
+    pub fn send_interrupt(&self) -> crate::Result<()> {
+        self.send_control_char(crate::ControlChar::Intr)
+    }
+
+    /// Sends the quit character (`VQUIT`, usually `^\`) to the pty,
+    /// which will send `SIGQUIT` to the foreground process group if
+    /// `ISIG` is enabled. The character is looked up in the current terminal
+    /// attributes of the pty, so this works even if it has been remapped.
+    ///
+    /// # Errors
+    /// Returns an error if the terminal attributes could not be retrieved,
+    /// if the character is disabled, or if writing to the pty failed.
+    pub fn send_quit(&self) -> crate::Result<()> {
+        self.send_control_char(crate::ControlChar::Quit)
+    }
+
+    /// Sends the suspend character (`VSUSP`, usually `^Z`) to the pty,
+    /// which will send `SIGTSTP` to the foreground process group if
+    /// `ISIG` is enabled. The character is looked up in the current terminal
+    /// attributes of the pty, so this works even if it has been remapped.
+    ///
+    /// # Errors
+    /// Returns an error if the terminal attributes could not be retrieved,
+    /// if the character is disabled, or if writing to the pty failed.
+    pub fn send_suspend(&self) -> crate::Result<()> {
+        self.send_control_char(crate::ControlChar::Susp)
+    }
+
+    /// Sends the end of file character (`VEOF`, usually `^D`) to the pty,
+    /// which will cause the child's next read to return end of file if
+    /// the pty is in canonical mode. If there is unfinished input on the
+    /// current line, the character instead just sends that input to the
+    /// child without a trailing newline, and this must be called a second
+    /// time to signal end of file. The character is looked up in the
+    /// current terminal attributes of the pty, so this works even if it has
+    /// been remapped.
+    ///
+    /// # Errors
+    /// Returns an error if the terminal attributes could not be retrieved,
+    /// if the character is disabled, or if writing to the pty failed.
+    pub fn send_eof(&self) -> crate::Result<()> {
+        self.send_control_char(crate::ControlChar::Eof)
+    }
+
+    /// Sends the erase character (`VERASE`, usually `^?`) to the pty,
+    /// which will erase the previous character of input if the pty is in
+    /// canonical mode. The character is looked up in the current terminal
+    /// attributes of the pty, so this works even if it has been remapped.
+    ///
+    /// # Errors
+    /// Returns an error if the terminal attributes could not be retrieved,
+    /// if the character is disabled, or if writing to the pty failed.
+    pub fn send_erase(&self) -> crate::Result<()> {
+        self.send_control_char(crate::ControlChar::Erase)
+    }
+
+    fn send_control_char(&self, cc: crate::ControlChar) -> crate::Result<()> {
+        let byte = self.0.control_char(cc)?;
+        (&self.0).write_all(&[byte])?;
+        Ok(())
+    }
+
+    /// Reads all remaining output from the pty until every process holding
+    /// the child end of the pty open has closed it (typically, when the
+    /// child process and any other processes in its session have exited),
+    /// and returns it.
+    ///
+    /// Note that this will never return if the [`Pts`] used to spawn the
+    /// child is still open.
+    ///
+    /// # Errors
+    /// Returns an error if reading from the pty failed.
+    pub fn drain_until_hangup(&mut self) -> crate::Result<Vec<u8>> {
+        let mut output = vec![];
+        std::io::Read::read_to_end(self, &mut output)?;
+        Ok(output)
+    }
+
+    /// Creates a pty from a file descriptor for the parent end of an existing
+    /// pty, such as one received from another process. The file descriptor
+    /// is put into blocking mode (note that this also affects any other file
+    /// descriptors for the same pty, including in other processes).
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to put the file descriptor into
+    /// blocking mode.
+    pub fn from_fd(fd: std::os::fd::OwnedFd) -> crate::Result<Self> {
+        let pty = crate::sys::Pty::from_fd(fd);
+        pty.set_blocking()?;
+        Ok(Self(pty))
+    }
+
+    /// Returns the current state of the pty, to be sent along with it via
+    /// [`send_to`](Self::send_to).
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to retrieve the terminal size,
+    /// the terminal attributes, or the index of the pty.
+    pub fn metadata(&self) -> crate::Result<crate::PtyMetadata> {
+        crate::PtyMetadata::new(&self.0)
+    }
+
+    /// Sends the pty to another process over a unix socket (as
+    /// `SCM_RIGHTS` ancillary data), along with the given metadata. The
+    /// other process can receive it with [`recv_from`](Self::recv_from) (or
+    /// [`Pty::recv_from`](crate::Pty::recv_from)).
+    ///
+    /// The pty remains open in this process until it is dropped, which
+    /// should typically happen once it has been sent, since both processes
+    /// reading from the same pty will result in each one seeing only part of
+    /// the output.
+    ///
+    /// # Errors
+    /// Returns an error if sending to the socket failed.
+    pub fn send_to(
+        &self,
+        stream: &std::os::unix::net::UnixStream,
+        metadata: &crate::PtyMetadata,
+    ) -> crate::Result<()> {
+        crate::transfer::send(
+            std::os::fd::AsFd::as_fd(stream),
+            &self.0,
+            metadata,
+        )?;
+        Ok(())
+    }
+
+    /// Receives a pty sent by [`send_to`](Self::send_to) (or
+    /// [`Pty::send_to`](crate::Pty::send_to)) from a unix socket, along with
+    /// the metadata it was sent with. The pty is set up as with
+    /// [`from_fd`](Self::from_fd).
+    ///
+    /// # Errors
+    /// Returns an error if receiving from the socket failed, if the data
+    /// received was not sent by `send_to`, or if setting up the pty failed.
+    pub fn recv_from(
+        stream: &std::os::unix::net::UnixStream,
+    ) -> crate::Result<(Self, crate::PtyMetadata)> {
+        let (pty, metadata) =
+            crate::transfer::recv(std::os::fd::AsFd::as_fd(stream))?;
+        pty.set_blocking()?;
+        Ok((Self(pty), metadata))
+    }
+
     /// Opens a file descriptor for the other end of the pty, which should be
     /// attached to the child process running in it. See
     /// [`Command::spawn`](crate::blocking::Command::spawn).
@@ -28,6 +282,33 @@ impl Pty {
     pub fn pts(&self) -> crate::Result<Pts> {
         Ok(Pts(self.0.pts()?))
     }
+
+    /// Returns the index of the pty, as used in the name of its device node
+    /// (for instance, `3` for `/dev/pts/3`). This is mainly useful for
+    /// logging.
+    ///
+    /// # Errors
+    /// Returns an error if the index could not be determined.
+    pub fn pts_index(&self) -> crate::Result<u32> {
+        self.0.pts_index()
+    }
+
+    /// Sets whether the pty is in non-blocking mode, in which case reads and
+    /// writes which would block return an error of kind
+    /// [`WouldBlock`](std::io::ErrorKind::WouldBlock) instead. This is
+    /// required when polling the pty with an external event loop. See also
+    /// [`PtyBuilder::nonblocking`](crate::blocking::PtyBuilder::nonblocking).
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to change the mode of the pty.
+    pub fn set_nonblocking(&self, nonblocking: bool) -> crate::Result<()> {
+        if nonblocking {
+            self.0.set_nonblocking()?;
+        } else {
+            self.0.set_blocking()?;
+        }
+        Ok(())
+    }
 }
 
 impl From<Pty> for std::os::fd::OwnedFd {
@@ -59,6 +340,13 @@ impl std::io::Write for Pty {
         self.0.write(buf)
     }
 
+    fn write_vectored(
+        &mut self,
+        bufs: &[std::io::IoSlice<'_>],
+    ) -> std::io::Result<usize> {
+        self.0.write_vectored(bufs)
+    }
+
     fn flush(&mut self) -> std::io::Result<()> {
         self.0.flush()
     }
@@ -75,12 +363,85 @@ impl std::io::Write for &Pty {
         (&self.0).write(buf)
     }
 
+    fn write_vectored(
+        &mut self,
+        bufs: &[std::io::IoSlice<'_>],
+    ) -> std::io::Result<usize> {
+        (&self.0).write_vectored(bufs)
+    }
+
     fn flush(&mut self) -> std::io::Result<()> {
         (&self.0).flush()
     }
 }
 
+/// Allows the pty to be registered with a [`mio::Poll`]. The pty must be in
+/// non-blocking mode (see [`Pty::set_nonblocking`]) before it is registered.
+#[cfg(feature = "mio")]
+impl mio::event::Source for Pty {
+    fn register(
+        &mut self,
+        registry: &mio::Registry,
+        token: mio::Token,
+        interests: mio::Interest,
+    ) -> std::io::Result<()> {
+        let fd = std::os::fd::AsRawFd::as_raw_fd(&self.0);
+        mio::event::Source::register(
+            &mut mio::unix::SourceFd(&fd),
+            registry,
+            token,
+            interests,
+        )
+    }
+
+    fn reregister(
+        &mut self,
+        registry: &mio::Registry,
+        token: mio::Token,
+        interests: mio::Interest,
+    ) -> std::io::Result<()> {
+        let fd = std::os::fd::AsRawFd::as_raw_fd(&self.0);
+        mio::event::Source::reregister(
+            &mut mio::unix::SourceFd(&fd),
+            registry,
+            token,
+            interests,
+        )
+    }
+
+    fn deregister(
+        &mut self,
+        registry: &mio::Registry,
+    ) -> std::io::Result<()> {
+        let fd = std::os::fd::AsRawFd::as_raw_fd(&self.0);
+        mio::event::Source::deregister(
+            &mut mio::unix::SourceFd(&fd),
+            registry,
+        )
+    }
+}
+
 /// The child end of the pty
 ///
 /// See [`Pty::pts`] and [`Command::spawn`](crate::blocking::Command::spawn)
 pub struct Pts(pub(crate) crate::sys::Pts);
+
+impl Pts {
+    /// Returns the terminal size as seen from the child end of the pty.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to retrieve the terminal size.
+    pub fn size(&self) -> crate::Result<crate::Size> {
+        self.0.term_size()
+    }
+
+    /// Returns the path of the device node for the child end of the pty
+    /// (for instance, `/dev/pts/3`). This is intended for display purposes
+    /// only: the pty is not opened through this path, and it may not refer
+    /// to the same device if the pty was allocated in a different mount
+    /// namespace.
+    #[must_use]
+    pub fn path(&self) -> &std::path::Path {
+        self.0.path()
+    }
+}
@@ -0,0 +1,151 @@
+/// Propagates size changes of the terminal attached to stdin to a set of
+/// ptys.
+///
+/// A background thread listens for `SIGWINCH` (via a self-pipe), and when
+/// the host terminal is resized, calls
+/// [`resize`](crate::blocking::Pty::resize) on each registered pty with the
+/// new size. Bursts of resize events (such as those produced while dragging
+/// a window border) are coalesced, so that the ptys are only resized once
+/// the host terminal has stopped changing size for the debounce interval.
+///
+/// The forwarder holds its own handle to each registered pty, so they will
+/// not be closed until they are [removed](Self::remove) or the forwarder is
+/// dropped. Dropping the forwarder stops the background thread.
+pub struct ResizeForwarder {
+    targets: std::sync::Arc<crate::resize::Targets>,
+    signal: signal_hook_registry::SigId,
+    stop: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    pipe_w: std::os::fd::OwnedFd,
+    thread: Option<std::thread::JoinHandle<()>>,
+}
+
+impl ResizeForwarder {
+    /// Starts listening for resizes of the host terminal, with the default
+    /// debounce interval of 50ms.
+    ///
+    /// # Errors
+    /// Returns an error if the `SIGWINCH` handler could not be installed.
+    pub fn new() -> crate::Result<Self> {
+        Self::with_debounce(crate::resize::DEFAULT_DEBOUNCE)
+    }
+
+    /// Starts listening for resizes of the host terminal, waiting until no
+    /// resizes have happened for `debounce` before propagating the new
+    /// size.
+    ///
+    /// # Errors
+    /// Returns an error if the `SIGWINCH` handler could not be installed.
+    pub fn with_debounce(
+        debounce: std::time::Duration,
+    ) -> crate::Result<Self> {
+        let (pipe_r, pipe_w) =
+            rustix::pipe::pipe_with(rustix::pipe::PipeFlags::CLOEXEC)?;
+        // the signal handler must never block
+        rustix::fs::fcntl_setfl(&pipe_w, rustix::fs::OFlags::NONBLOCK)?;
+
+        let raw_w = std::os::fd::AsRawFd::as_raw_fd(&pipe_w);
+        // SAFETY: write(2) is async-signal-safe, and the write end of the
+        // pipe is only closed after the handler is unregistered
+        let signal = unsafe {
+            signal_hook_registry::register(libc::SIGWINCH, move || {
+                libc::write(raw_w, [0_u8].as_ptr().cast(), 1);
+            })
+        }?;
+
+        let targets = std::sync::Arc::new(crate::resize::Targets::default());
+        let stop =
+            std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let thread = std::thread::Builder::new()
+            .name("pty-process-resize".to_string())
+            .spawn({
+                let targets = std::sync::Arc::clone(&targets);
+                let stop = std::sync::Arc::clone(&stop);
+                move || run(&pipe_r, &targets, &stop, debounce)
+            });
+        let thread = match thread {
+            Ok(thread) => thread,
+            Err(e) => {
+                signal_hook_registry::unregister(signal);
+                return Err(e.into());
+            }
+        };
+
+        Ok(Self {
+            targets,
+            signal,
+            stop,
+            pipe_w,
+            thread: Some(thread),
+        })
+    }
+
+    /// Registers a pty to be kept in sync with the size of the host
+    /// terminal, and immediately resizes it to the current size of the host
+    /// terminal. The returned [`ResizeTarget`](crate::ResizeTarget) can be
+    /// passed to [`remove`](Self::remove) once the pty is no longer needed.
+    ///
+    /// # Errors
+    /// Returns an error if the pty's file descriptor could not be
+    /// duplicated, or if the pty could not be resized.
+    pub fn add(
+        &self,
+        pty: &impl std::os::fd::AsFd,
+    ) -> crate::Result<crate::ResizeTarget> {
+        self.targets.add(pty.as_fd())
+    }
+
+    /// Stops keeping the given pty in sync with the size of the host
+    /// terminal, and closes the forwarder's handle to it. Returns `false` if
+    /// the pty was not registered (for instance, if it was already removed).
+    #[must_use]
+    pub fn remove(&self, target: crate::ResizeTarget) -> bool {
+        self.targets.remove(target)
+    }
+}
+
+impl Drop for ResizeForwarder {
+    fn drop(&mut self) {
+        signal_hook_registry::unregister(self.signal);
+        self.stop.store(true, std::sync::atomic::Ordering::SeqCst);
+        let _ = rustix::io::write(&self.pipe_w, &[0]);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn run(
+    pipe_r: &std::os::fd::OwnedFd,
+    targets: &crate::resize::Targets,
+    stop: &std::sync::atomic::AtomicBool,
+    debounce: std::time::Duration,
+) {
+    let debounce = i32::try_from(debounce.as_millis()).unwrap_or(i32::MAX);
+    let mut buf = [0_u8; 64];
+    loop {
+        // wait for a signal, and then until no further signals arrive
+        // within the debounce interval
+        let mut timeout = -1;
+        loop {
+            let mut fds = [rustix::event::PollFd::new(
+                pipe_r,
+                rustix::event::PollFlags::IN,
+            )];
+            match rustix::event::poll(&mut fds, timeout) {
+                Ok(0) => break,
+                Ok(_) => {}
+                Err(rustix::io::Errno::INTR) => continue,
+                Err(_) => return,
+            }
+            if stop.load(std::sync::atomic::Ordering::SeqCst) {
+                return;
+            }
+            match rustix::io::read(pipe_r, &mut buf) {
+                Ok(0) | Err(rustix::io::Errno::INTR) => {}
+                Ok(_) => timeout = debounce,
+                Err(_) => return,
+            }
+        }
+        targets.sync();
+    }
+}
@@ -0,0 +1,110 @@
+//! Blocking equivalent of [`pty_process::screen`](crate::screen)
+
+pub use crate::screen::{Cell, Color, Screen};
+
+/// Wrapper around a [`Pty`](crate::blocking::Pty) which keeps track of the
+/// state of the terminal as output is read from it.
+///
+/// Reads and writes are passed through to the underlying pty unchanged, but
+/// all data read is also processed by a terminal emulator whose state is
+/// available via [`screen`](Self::screen).
+pub struct Terminal {
+    pty: crate::blocking::Pty,
+    parser: vt100::Parser,
+}
+
+impl Terminal {
+    /// Creates a new terminal wrapping the given pty. The initial size of the
+    /// screen is taken from the current size of the pty. If the pty has not
+    /// been given a size yet, it is resized to 24x80 first, since no output
+    /// can be processed for an empty screen.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to retrieve or set the terminal
+    /// size.
+    pub fn new(pty: crate::blocking::Pty) -> crate::Result<Self> {
+        Self::with_scrollback(pty, 0)
+    }
+
+    /// Creates a new terminal wrapping the given pty, which will retain up
+    /// to `scrollback_len` rows of scrollback. The initial size is chosen
+    /// as for [`new`](Self::new).
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to retrieve or set the terminal
+    /// size.
+    pub fn with_scrollback(
+        pty: crate::blocking::Pty,
+        scrollback_len: usize,
+    ) -> crate::Result<Self> {
+        let mut size = pty.size()?;
+        if size.rows() == 0 || size.cols() == 0 {
+            size = crate::Size::new(24, 80);
+            pty.resize(size)?;
+        }
+        Ok(Self {
+            pty,
+            parser: vt100::Parser::new(
+                size.rows(),
+                size.cols(),
+                scrollback_len,
+            ),
+        })
+    }
+
+    /// Returns the current state of the terminal.
+    #[must_use]
+    pub fn screen(&self) -> &Screen {
+        self.parser.screen()
+    }
+
+    /// Change the terminal size associated with the pty, and resize the
+    /// tracked screen to match.
+    ///
+    /// # Errors
+    /// Returns an error if either dimension of `size` is zero (since no
+    /// output can be processed for an empty screen), or if we were unable
+    /// to set the terminal size.
+    pub fn resize(&mut self, size: crate::Size) -> crate::Result<()> {
+        crate::screen::check_size(size)?;
+        self.pty.resize(size)?;
+        self.parser.set_size(size.rows(), size.cols());
+        Ok(())
+    }
+
+    /// Returns a reference to the underlying pty.
+    #[must_use]
+    pub fn get_ref(&self) -> &crate::blocking::Pty {
+        &self.pty
+    }
+
+    /// Returns a mutable reference to the underlying pty. Data read from the
+    /// pty directly will not be reflected in the tracked screen.
+    pub fn get_mut(&mut self) -> &mut crate::blocking::Pty {
+        &mut self.pty
+    }
+
+    /// Returns the underlying pty.
+    #[must_use]
+    pub fn into_inner(self) -> crate::blocking::Pty {
+        self.pty
+    }
+}
+
+impl std::io::Read for Terminal {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let bytes = self.pty.read(buf)?;
+        self.parser.process(&buf[..bytes]);
+        Ok(bytes)
+    }
+}
+
+impl std::io::Write for Terminal {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.pty.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.pty.flush()
+    }
+}
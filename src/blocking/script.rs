@@ -0,0 +1,66 @@
+//! Blocking equivalent of [`pty_process::script`](crate::script)
+
+pub use crate::script::{Event, Format, Reader, Writer};
+
+/// Wrapper around the read half of a pty (such as a
+/// [`Pty`](crate::blocking::Pty) or a `&Pty`) which records everything read
+/// from it to a typescript and timing file pair.
+pub struct Recording<R, L: std::io::Write, T: std::io::Write> {
+    pty: R,
+    writer: Writer<L, T>,
+}
+
+impl<R: std::io::Read, L: std::io::Write, T: std::io::Write>
+    Recording<R, L, T>
+{
+    /// Starts recording data read from `pty` to the given typescript and
+    /// timing file, in the given format. `size` is recorded in the header of
+    /// the typescript.
+    ///
+    /// # Errors
+    /// Returns an error if writing the headers failed.
+    pub fn new(
+        pty: R,
+        log: L,
+        timing: T,
+        format: Format,
+        size: crate::Size,
+    ) -> crate::Result<Self> {
+        Ok(Self {
+            pty,
+            writer: Writer::new(log, timing, format, size)?,
+        })
+    }
+
+    /// Returns a mutable reference to the underlying writer, for instance
+    /// to record input or resize events.
+    pub fn writer(&mut self) -> &mut Writer<L, T> {
+        &mut self.writer
+    }
+
+    /// Returns a reference to the wrapped pty.
+    pub fn get_ref(&self) -> &R {
+        &self.pty
+    }
+
+    /// Stops recording, writing the typescript trailer with the given exit
+    /// code, and returns the wrapped pty along with the typescript and
+    /// timing file writers.
+    ///
+    /// # Errors
+    /// Returns an error if writing the trailer failed.
+    pub fn finish(self, exit_code: Option<i32>) -> crate::Result<(R, L, T)> {
+        let (log, timing) = self.writer.finish(exit_code)?;
+        Ok((self.pty, log, timing))
+    }
+}
+
+impl<R: std::io::Read, L: std::io::Write, T: std::io::Write> std::io::Read
+    for Recording<R, L, T>
+{
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let bytes = self.pty.read(buf)?;
+        self.writer.record_output(&buf[..bytes])?;
+        Ok(bytes)
+    }
+}
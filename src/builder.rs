@@ -0,0 +1,124 @@
+/// The settings which a pty builder applies to a new pty, shared between the
+/// async and blocking builders.
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    pub size: Option<crate::Size>,
+    pub termios: Option<crate::Termios>,
+    pub echo: Option<bool>,
+    pub raw: bool,
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub ptmx: Option<std::path::PathBuf>,
+}
+
+impl Options {
+    /// Allocates a new pty and applies the settings to it. Nothing else has
+    /// access to the pty at this point, so the child end can't observe it
+    /// in an intermediate state.
+    pub fn open(&self) -> crate::Result<crate::sys::Pty> {
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        let pty = match &self.ptmx {
+            Some(ptmx) => crate::sys::Pty::open_at(ptmx)?,
+            None => crate::sys::Pty::open()?,
+        };
+        #[cfg(not(any(target_os = "linux", target_os = "android")))]
+        let pty = crate::sys::Pty::open()?;
+        if self.termios.is_some() || self.echo.is_some() || self.raw {
+            let mut termios = match &self.termios {
+                Some(termios) => termios.clone(),
+                None => pty.termios()?,
+            };
+            if self.raw {
+                termios.make_raw();
+            }
+            if let Some(echo) = self.echo {
+                termios.set_echo(echo);
+            }
+            pty.set_termios(crate::SetArg::Now, &termios)?;
+        }
+        if let Some(size) = self.size {
+            pty.set_term_size(size)?;
+        }
+        Ok(pty)
+    }
+}
+
+/// Builder for a [`Pty`](crate::Pty) which is configured before it is
+/// returned
+///
+/// Obtained via [`Pty::builder`](crate::Pty::builder). Since the settings
+/// are all applied before any [`Pts`](crate::Pts) can be opened, a child
+/// process spawned on the resulting pty will see them from the start,
+/// rather than racing against a later call to
+/// [`resize`](crate::Pty::resize) or
+/// [`set_termios`](crate::Pty::set_termios).
+///
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() -> pty_process::Result<()> {
+/// let pty = pty_process::Pty::builder()
+///     .size(pty_process::Size::new(24, 80))
+///     .echo(false)
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "async")]
+#[derive(Debug, Clone)]
+pub struct PtyBuilder {
+    options: Options,
+}
+
+#[cfg(feature = "async")]
+impl PtyBuilder {
+    pub(crate) fn new() -> Self {
+        Self {
+            options: Options::default(),
+        }
+    }
+
+    /// Sets the initial terminal size of the pty.
+    pub fn size(&mut self, size: crate::Size) -> &mut Self {
+        self.options.size = Some(size);
+        self
+    }
+
+    /// Sets the initial terminal attributes of the pty. The
+    /// [`echo`](Self::echo) and [`raw`](Self::raw) settings are applied on
+    /// top of these.
+    pub fn termios(&mut self, termios: crate::Termios) -> &mut Self {
+        self.options.termios = Some(termios);
+        self
+    }
+
+    /// Sets whether input characters are initially echoed (`ECHO`).
+    pub fn echo(&mut self, echo: bool) -> &mut Self {
+        self.options.echo = Some(echo);
+        self
+    }
+
+    /// Sets whether the pty is initially in raw mode (see
+    /// [`Termios::make_raw`](crate::Termios::make_raw)).
+    pub fn raw(&mut self, raw: bool) -> &mut Self {
+        self.options.raw = raw;
+        self
+    }
+
+    /// Allocates the pty from the given `ptmx` device node (see
+    /// [`Pty::open_at`](crate::Pty::open_at)), rather than from
+    /// `/dev/ptmx`.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn ptmx(&mut self, ptmx: impl AsRef<std::path::Path>) -> &mut Self {
+        self.options.ptmx = Some(ptmx.as_ref().to_path_buf());
+        self
+    }
+
+    /// Allocates a new pty with the configured settings.
+    ///
+    /// # Errors
+    /// Returns an error if the pty failed to be allocated, if any of the
+    /// settings could not be applied, or if we were unable to put it into
+    /// non-blocking mode.
+    pub fn build(&self) -> crate::Result<crate::Pty> {
+        crate::Pty::from_sys(self.options.open()?)
+    }
+}
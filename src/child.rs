@@ -0,0 +1,176 @@
+/// A child process running in a pty, along with the pty itself
+///
+/// Returned by [`Command::spawn_pty`](crate::Command::spawn_pty). Reading
+/// from and writing to a `PtyChild` reads from and writes to the pty.
+///
+/// When a `PtyChild` is dropped, the pty is closed first (which will send
+/// `SIGHUP` to the child's session, as happens when a terminal window is
+/// closed), and then the handle to the child process is dropped. Like
+/// [`tokio::process::Child`], the child process is not otherwise killed or
+/// waited for.
+pub struct PtyChild {
+    // field order matters here: fields are dropped in declaration order,
+    // and the pty should be closed before the child handle is dropped
+    pty: crate::Pty,
+    child: tokio::process::Child,
+}
+
+impl PtyChild {
+    pub(crate) fn new(pty: crate::Pty, child: tokio::process::Child) -> Self {
+        Self { pty, child }
+    }
+
+    /// Returns a reference to the pty.
+    #[must_use]
+    pub fn pty(&self) -> &crate::Pty {
+        &self.pty
+    }
+
+    /// Returns a mutable reference to the pty.
+    pub fn pty_mut(&mut self) -> &mut crate::Pty {
+        &mut self.pty
+    }
+
+    /// Returns a reference to the child process.
+    #[must_use]
+    pub fn child(&self) -> &tokio::process::Child {
+        &self.child
+    }
+
+    /// Returns a mutable reference to the child process.
+    pub fn child_mut(&mut self) -> &mut tokio::process::Child {
+        &mut self.child
+    }
+
+    /// Splits the pty into a read half and a write half. See
+    /// [`Pty::split`](crate::Pty::split).
+    pub fn split(&mut self) -> (crate::ReadPty<'_>, crate::WritePty<'_>) {
+        self.pty.split()
+    }
+
+    /// Change the terminal size associated with the pty.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to set the terminal size.
+    pub fn resize(&self, size: crate::Size) -> crate::Result<()> {
+        self.pty.resize(size)
+    }
+
+    /// Returns the terminal size currently associated with the pty.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to retrieve the terminal size.
+    pub fn size(&self) -> crate::Result<crate::Size> {
+        self.pty.size()
+    }
+
+    /// Returns the process id of the child process, or `None` if it has
+    /// already been waited for. See [`tokio::process::Child::id`].
+    #[must_use]
+    pub fn id(&self) -> Option<u32> {
+        self.child.id()
+    }
+
+    /// Waits for the child process to exit. See
+    /// [`tokio::process::Child::wait`].
+    ///
+    /// # Errors
+    /// Returns an error if waiting for the child process failed.
+    pub async fn wait(&mut self) -> crate::Result<std::process::ExitStatus> {
+        Ok(self.child.wait().await?)
+    }
+
+    /// Returns the exit status of the child process if it has exited,
+    /// without blocking. See [`tokio::process::Child::try_wait`].
+    ///
+    /// # Errors
+    /// Returns an error if checking the status of the child process failed.
+    pub fn try_wait(
+        &mut self,
+    ) -> crate::Result<Option<std::process::ExitStatus>> {
+        Ok(self.child.try_wait()?)
+    }
+
+    /// Sends `SIGKILL` to the child process and waits for it to exit. See
+    /// [`tokio::process::Child::kill`].
+    ///
+    /// # Errors
+    /// Returns an error if the signal could not be sent, or if waiting for
+    /// the child process failed.
+    pub async fn kill(&mut self) -> crate::Result<()> {
+        Ok(self.child.kill().await?)
+    }
+
+    /// Reads all remaining output from the pty until it has been closed by
+    /// every process in the child's session, then waits for the child to
+    /// exit, and returns its exit status along with the output. Unlike
+    /// racing a read against [`wait`](Self::wait), this ensures that no
+    /// output written by the child before it exited is lost.
+    ///
+    /// Note that this will not return while anything else holds the child
+    /// end of the pty open, such as a [`Pts`](crate::Pts) obtained
+    /// from the pty.
+    ///
+    /// # Errors
+    /// Returns an error if reading from the pty failed, or if waiting for
+    /// the child process failed.
+    pub async fn wait_with_output(
+        mut self,
+    ) -> crate::Result<(std::process::ExitStatus, Vec<u8>)> {
+        let output = self.pty.drain_until_hangup().await?;
+        let status = self.child.wait().await?;
+        Ok((status, output))
+    }
+
+    /// Returns the pty and the child process, in that order.
+    #[must_use]
+    pub fn into_parts(self) -> (crate::Pty, tokio::process::Child) {
+        (self.pty, self.child)
+    }
+}
+
+impl std::os::fd::AsFd for PtyChild {
+    fn as_fd(&self) -> std::os::fd::BorrowedFd<'_> {
+        self.pty.as_fd()
+    }
+}
+
+impl std::os::fd::AsRawFd for PtyChild {
+    fn as_raw_fd(&self) -> std::os::fd::RawFd {
+        self.pty.as_raw_fd()
+    }
+}
+
+impl tokio::io::AsyncRead for PtyChild {
+    fn poll_read(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &mut tokio::io::ReadBuf,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        std::pin::Pin::new(&mut self.get_mut().pty).poll_read(cx, buf)
+    }
+}
+
+impl tokio::io::AsyncWrite for PtyChild {
+    fn poll_write(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &[u8],
+    ) -> std::task::Poll<std::io::Result<usize>> {
+        std::pin::Pin::new(&mut self.get_mut().pty).poll_write(cx, buf)
+    }
+
+    fn poll_flush(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        std::pin::Pin::new(&mut self.get_mut().pty).poll_flush(cx)
+    }
+
+    fn poll_shutdown(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        std::pin::Pin::new(&mut self.get_mut().pty).poll_shutdown(cx)
+    }
+}
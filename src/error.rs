@@ -1,13 +1,23 @@
 /// Error type for errors from this crate
 #[derive(Debug)]
 pub enum Error {
-    /// error came from std::io::Error
+    /// error came from `std::io::Error`
     Io(std::io::Error),
-    /// error came from nix::Error
+    /// error came from `rustix::io::Errno`
     Rustix(rustix::io::Errno),
     /// unsplit was called on halves of two different ptys
     #[cfg(feature = "async")]
     Unsplit(crate::OwnedReadPty, crate::OwnedWritePty),
+    /// unsplit was called on halves of two different
+    /// [`async_io`](crate::async_io) ptys
+    #[cfg(feature = "async-io")]
+    AsyncIoUnsplit(
+        crate::async_io::OwnedReadPty,
+        crate::async_io::OwnedWritePty,
+    ),
+    /// timed out waiting for the expected output
+    #[cfg(feature = "expect")]
+    Timeout,
 }
 
 impl std::fmt::Display for Error {
@@ -19,6 +29,12 @@ impl std::fmt::Display for Error {
             Self::Unsplit(..) => {
                 write!(f, "unsplit called on halves of two different ptys")
             }
+            #[cfg(feature = "async-io")]
+            Self::AsyncIoUnsplit(..) => {
+                write!(f, "unsplit called on halves of two different ptys")
+            }
+            #[cfg(feature = "expect")]
+            Self::Timeout => write!(f, "timed out waiting for output"),
         }
     }
 }
@@ -42,6 +58,10 @@ impl std::error::Error for Error {
             Self::Rustix(e) => Some(e),
             #[cfg(feature = "async")]
             Self::Unsplit(..) => None,
+            #[cfg(feature = "async-io")]
+            Self::AsyncIoUnsplit(..) => None,
+            #[cfg(feature = "expect")]
+            Self::Timeout => None,
         }
     }
 }
@@ -0,0 +1,339 @@
+//! Expect-style automation of programs running in a pty.
+//!
+//! A [`Session`] wraps a [`Pty`](crate::Pty) and provides methods for
+//! sending input to the child process and waiting until its output matches
+//! a given [`Pattern`], with a timeout:
+//!
+//! ```no_run
+//! # #[cfg(feature = "async")]
+//! # #[tokio::main]
+//! # async fn main() -> pty_process::Result<()> {
+//! let pty = pty_process::Pty::new()?;
+//! let mut cmd = pty_process::Command::new("python3");
+//! let child = cmd.spawn(&pty.pts()?)?;
+//! let mut session = pty_process::expect::Session::new(pty);
+//! let timeout = std::time::Duration::from_secs(5);
+//! session.expect(">>> ", timeout).await?;
+//! session.send_line("1 + 1").await?;
+//! session.expect(">>> ", timeout).await?;
+//! assert!(session.before().starts_with(b"1 + 1\r\n2"));
+//! session.send_control('d').await?;
+//! session.expect_eof(timeout).await?;
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "async"))]
+//! # fn main() {}
+//! ```
+//!
+//! See [`blocking::expect::Session`](crate::blocking::expect::Session) for
+//! the blocking equivalent.
+
+/// A pattern to wait for in the output of the child process.
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    /// Matches the given sequence of bytes exactly.
+    Exact(Vec<u8>),
+    /// Matches the given regular expression.
+    Regex(regex::bytes::Regex),
+    /// Matches once all output has been read and the pty has been closed by
+    /// the child process.
+    Eof,
+}
+
+impl From<&str> for Pattern {
+    fn from(s: &str) -> Self {
+        Self::Exact(s.as_bytes().to_vec())
+    }
+}
+
+impl From<String> for Pattern {
+    fn from(s: String) -> Self {
+        Self::Exact(s.into_bytes())
+    }
+}
+
+impl From<&[u8]> for Pattern {
+    fn from(s: &[u8]) -> Self {
+        Self::Exact(s.to_vec())
+    }
+}
+
+impl From<Vec<u8>> for Pattern {
+    fn from(s: Vec<u8>) -> Self {
+        Self::Exact(s)
+    }
+}
+
+impl From<regex::bytes::Regex> for Pattern {
+    fn from(re: regex::bytes::Regex) -> Self {
+        Self::Regex(re)
+    }
+}
+
+/// Output buffering and pattern matching shared between the async and
+/// blocking sessions.
+#[derive(Debug, Default)]
+pub(crate) struct Buffer {
+    buf: Vec<u8>,
+    before: Vec<u8>,
+    after: Vec<u8>,
+    eof: bool,
+}
+
+impl Buffer {
+    pub fn data(&self) -> &[u8] {
+        &self.buf
+    }
+
+    pub fn before(&self) -> &[u8] {
+        &self.before
+    }
+
+    pub fn after(&self) -> &[u8] {
+        &self.after
+    }
+
+    pub fn is_eof(&self) -> bool {
+        self.eof
+    }
+
+    pub fn push(&mut self, bytes: &[u8]) {
+        self.buf.extend_from_slice(bytes);
+    }
+
+    pub fn set_eof(&mut self) {
+        self.eof = true;
+    }
+
+    /// Looks for the earliest match of any of the given patterns in the
+    /// buffered output, preferring earlier patterns when two matches start
+    /// at the same position. On success, the matched output is consumed and
+    /// the index of the matching pattern is returned.
+    pub fn try_match(&mut self, patterns: &[Pattern]) -> Option<usize> {
+        let mut best: Option<(usize, usize, usize)> = None;
+        for (i, pattern) in patterns.iter().enumerate() {
+            let found = match pattern {
+                Pattern::Exact(needle) => find(&self.buf, needle)
+                    .map(|start| (start, start + needle.len())),
+                Pattern::Regex(re) => {
+                    re.find(&self.buf).map(|m| (m.start(), m.end()))
+                }
+                Pattern::Eof => {
+                    self.eof.then_some((self.buf.len(), self.buf.len()))
+                }
+            };
+            if let Some((start, end)) = found {
+                match best {
+                    Some((_, best_start, _)) if best_start <= start => {}
+                    _ => best = Some((i, start, end)),
+                }
+            }
+        }
+
+        let (i, start, end) = best?;
+        self.after = self.buf.drain(..end).collect();
+        self.before = self.after.drain(..start).collect();
+        Some(i)
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    if needle.is_empty() {
+        return Some(0);
+    }
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// Returns the byte sent by typing the given character while holding down
+/// the control key.
+pub(crate) fn control_byte(c: char) -> crate::Result<u8> {
+    let byte = match c.to_ascii_uppercase() {
+        c @ ('@'..='_') => {
+            u8::try_from(c).unwrap_or_else(|_| unreachable!()) - b'@'
+        }
+        '?' => 0x7f,
+        _ => {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("no control character corresponds to {c:?}"),
+            )
+            .into())
+        }
+    };
+    Ok(byte)
+}
+
+/// Wrapper around a [`Pty`](crate::Pty) which allows waiting for
+/// specific output from the child process.
+#[cfg(feature = "async")]
+pub struct Session {
+    pty: crate::Pty,
+    buf: Buffer,
+}
+
+#[cfg(feature = "async")]
+impl Session {
+    /// Creates a new session reading from and writing to the given pty.
+    #[must_use]
+    pub fn new(pty: crate::Pty) -> Self {
+        Self {
+            pty,
+            buf: Buffer::default(),
+        }
+    }
+
+    /// Waits until the output of the child process matches `pattern`.
+    /// On success, [`before`](Self::before) will contain the output
+    /// preceding the match and [`after`](Self::after) will contain the
+    /// matched output itself.
+    ///
+    /// # Errors
+    /// Returns [`Error::Timeout`](crate::Error::Timeout) if the pattern
+    /// was not matched before `timeout` elapsed, an error of kind
+    /// [`UnexpectedEof`](std::io::ErrorKind::UnexpectedEof) if the pty
+    /// was closed before the pattern was matched, or an error if reading
+    /// from the pty failed.
+    pub async fn expect(
+        &mut self,
+        pattern: impl Into<Pattern>,
+        timeout: std::time::Duration,
+    ) -> crate::Result<()> {
+        self.expect_any([pattern], timeout).await.map(|_| ())
+    }
+
+    /// Waits until the output of the child process matches any of
+    /// `patterns`, and returns the index of the pattern which matched.
+    /// If several patterns match, the one whose match starts earliest in
+    /// the output is chosen.
+    ///
+    /// # Errors
+    /// See [`expect`](Self::expect).
+    pub async fn expect_any<I, P>(
+        &mut self,
+        patterns: I,
+        timeout: std::time::Duration,
+    ) -> crate::Result<usize>
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<Pattern>,
+    {
+        let patterns: Vec<_> = patterns.into_iter().map(Into::into).collect();
+        tokio::time::timeout(timeout, self.wait_for(&patterns))
+            .await
+            .map_err(|_| crate::Error::Timeout)?
+    }
+
+    /// Waits until the child process closes the pty. Note that this can
+    /// only happen once every handle to the child end of the pty (including
+    /// the `Pts` used to spawn the child) has been closed.
+    ///
+    /// # Errors
+    /// See [`expect`](Self::expect).
+    pub async fn expect_eof(
+        &mut self,
+        timeout: std::time::Duration,
+    ) -> crate::Result<()> {
+        self.expect(Pattern::Eof, timeout).await
+    }
+
+    /// Sends the given data to the child process.
+    ///
+    /// # Errors
+    /// Returns an error if writing to the pty failed.
+    pub async fn send(
+        &mut self,
+        data: impl AsRef<[u8]>,
+    ) -> crate::Result<()> {
+        tokio::io::AsyncWriteExt::write_all(&mut self.pty, data.as_ref())
+            .await?;
+        Ok(())
+    }
+
+    /// Sends the given line, followed by a newline, to the child
+    /// process.
+    ///
+    /// # Errors
+    /// Returns an error if writing to the pty failed.
+    pub async fn send_line(
+        &mut self,
+        line: impl AsRef<[u8]>,
+    ) -> crate::Result<()> {
+        self.send(line).await?;
+        self.send(b"\n").await
+    }
+
+    /// Sends the byte corresponding to pressing the given character
+    /// while holding the control key (so `send_control('c')` sends
+    /// `^C`).
+    ///
+    /// # Errors
+    /// Returns an error if `c` does not correspond to a control
+    /// character, or if writing to the pty failed.
+    pub async fn send_control(&mut self, c: char) -> crate::Result<()> {
+        let byte = control_byte(c)?;
+        self.send([byte]).await
+    }
+
+    /// Returns the output preceding the most recent match.
+    #[must_use]
+    pub fn before(&self) -> &[u8] {
+        self.buf.before()
+    }
+
+    /// Returns the output which was matched by the most recent match.
+    #[must_use]
+    pub fn after(&self) -> &[u8] {
+        self.buf.after()
+    }
+
+    /// Returns the output which has been read from the pty but not yet
+    /// consumed by a match.
+    #[must_use]
+    pub fn buffer(&self) -> &[u8] {
+        self.buf.data()
+    }
+
+    /// Returns a reference to the underlying pty.
+    #[must_use]
+    pub fn get_ref(&self) -> &crate::Pty {
+        &self.pty
+    }
+
+    /// Returns a mutable reference to the underlying pty. Reading from
+    /// the pty directly will bypass the session's buffering.
+    pub fn get_mut(&mut self) -> &mut crate::Pty {
+        &mut self.pty
+    }
+
+    /// Returns the underlying pty, discarding any buffered output.
+    #[must_use]
+    pub fn into_inner(self) -> crate::Pty {
+        self.pty
+    }
+
+    async fn wait_for(
+        &mut self,
+        patterns: &[Pattern],
+    ) -> crate::Result<usize> {
+        let mut buf = [0_u8; 4096];
+        loop {
+            if let Some(i) = self.buf.try_match(patterns) {
+                return Ok(i);
+            }
+            if self.buf.is_eof() {
+                return Err(std::io::Error::from(
+                    std::io::ErrorKind::UnexpectedEof,
+                )
+                .into());
+            }
+            match tokio::io::AsyncReadExt::read(&mut self.pty, &mut buf).await
+            {
+                Ok(0) => self.buf.set_eof(),
+                Ok(bytes) => self.buf.push(&buf[..bytes]),
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
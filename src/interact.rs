@@ -0,0 +1,132 @@
+use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
+
+/// How long to keep waiting for more output from the pty after the child
+/// process has exited, in case it is still held open by some other process.
+const DRAIN_TIMEOUT: std::time::Duration =
+    std::time::Duration::from_millis(100);
+
+/// Connects the current process's terminal to the given pty until the child
+/// process exits, and returns its exit status.
+///
+/// While this function is running, stdin is put into raw mode and all input
+/// is forwarded to the pty, and all output from the pty is written to
+/// stdout. The pty is initially resized to match the size of the terminal
+/// attached to stdin (if any). Once the child exits, any remaining output
+/// is written to stdout, and the original terminal settings are restored
+/// (this also happens if this function returns early or panics).
+///
+/// Note that the `Pts` used to spawn the child should be dropped before
+/// calling this function, since otherwise the pty will not be closed when
+/// the child exits.
+///
+/// # Errors
+/// Returns an error if the terminal settings of stdin could not be changed,
+/// if reading from or writing to stdin, stdout, or the pty failed, or if
+/// waiting for the child process failed.
+pub async fn interact(
+    pty: &mut crate::Pty,
+    child: &mut tokio::process::Child,
+) -> crate::Result<std::process::ExitStatus> {
+    if let Some(size) = crate::sys::host_term_size() {
+        pty.resize(size)?;
+    }
+    let _raw = crate::sys::RawGuard::new()?;
+
+    let mut stdin = Stdin::new();
+    let mut stdout = tokio::io::stdout();
+    let mut in_buf = [0_u8; 4096];
+    let mut out_buf = [0_u8; 4096];
+    let mut stdin_open = true;
+
+    loop {
+        tokio::select! {
+            bytes = stdin.read(&mut in_buf), if stdin_open => match bytes? {
+                0 => stdin_open = false,
+                bytes => pty.write_all(&in_buf[..bytes]).await?,
+            },
+            bytes = pty.read(&mut out_buf) => match bytes {
+                Ok(0) => break,
+                Ok(bytes) => {
+                    stdout.write_all(&out_buf[..bytes]).await?;
+                    stdout.flush().await?;
+                }
+                Err(e) => return Err(e.into()),
+            },
+            status = child.wait() => {
+                let status = status?;
+                drain(pty, &mut stdout, &mut out_buf).await?;
+                return Ok(status);
+            }
+        }
+    }
+
+    Ok(child.wait().await?)
+}
+
+async fn drain(
+    pty: &mut crate::Pty,
+    stdout: &mut tokio::io::Stdout,
+    buf: &mut [u8],
+) -> crate::Result<()> {
+    loop {
+        let Ok(bytes) =
+            tokio::time::timeout(DRAIN_TIMEOUT, pty.read(buf)).await
+        else {
+            return Ok(());
+        };
+        match bytes {
+            Ok(0) => return Ok(()),
+            Ok(bytes) => {
+                stdout.write_all(&buf[..bytes]).await?;
+                stdout.flush().await?;
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+/// Stdin, registered with the reactor when possible. `tokio::io::stdin`
+/// reads on a blocking thread which can't be cancelled, so a read which is
+/// still in progress when `interact` returns would swallow the next piece
+/// of input meant for the caller. This is only used as a fallback when
+/// stdin can't be polled (for instance, when it is a regular file).
+enum Stdin {
+    Fd(tokio::io::unix::AsyncFd<std::os::fd::BorrowedFd<'static>>),
+    Tokio(tokio::io::Stdin),
+}
+
+impl Stdin {
+    fn new() -> Self {
+        tokio::io::unix::AsyncFd::with_interest(
+            rustix::stdio::stdin(),
+            tokio::io::Interest::READABLE,
+        )
+        .map_or_else(|_| Self::Tokio(tokio::io::stdin()), Self::Fd)
+    }
+
+    async fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            Self::Fd(fd) => loop {
+                let mut guard = fd.readable().await?;
+                // stdin isn't necessarily in non-blocking mode (and we
+                // don't want to change that, since it is shared with other
+                // processes), so check that a read won't block before
+                // trying it
+                match guard.try_io(|fd| {
+                    let mut fds = [rustix::event::PollFd::new(
+                        fd.get_ref(),
+                        rustix::event::PollFlags::IN,
+                    )];
+                    if rustix::event::poll(&mut fds, 0)? == 0 {
+                        return Err(std::io::ErrorKind::WouldBlock.into());
+                    }
+                    Ok(rustix::io::read(fd.get_ref(), &mut *buf)?)
+                }) {
+                    Ok(bytes) => return bytes,
+                    Err(_would_block) => {}
+                }
+            },
+            Self::Tokio(stdin) => stdin.read(buf).await,
+        }
+    }
+}
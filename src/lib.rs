@@ -34,12 +34,68 @@
 //! the [`blocking`] variant), and can be used to communicate with the child
 //! process. The child process will also be made a session leader of a new
 //! session, and the controlling terminal of that session will be set to the
-//! given pty.
+//! given pty. Once every process holding the child end of the pty open has
+//! exited (note that this includes the [`Pts`](crate::blocking::Pts) used to
+//! spawn the child, so it should be dropped once the child has been spawned),
+//! reading from the pty will return end of file.
+//!
+//! Alternatively, [`Command::spawn_pty`](crate::Command::spawn_pty) (or
+//! [`blocking::Command::spawn_pty`](crate::blocking::Command::spawn_pty))
+//! allocates a pty of the given size and spawns the child on it, returning
+//! a [`PtyChild`](crate::PtyChild) which owns both.
+//!
+//! For the common case of simply connecting the current terminal to the
+//! child process until it exits, see [`interact`](crate::interact)
+//! (or [`blocking::interact`](crate::blocking::interact)). To keep the size
+//! of a pty in sync with the size of the current terminal, see
+//! [`ResizeForwarder`](crate::ResizeForwarder) (or
+//! [`blocking::ResizeForwarder`](crate::blocking::ResizeForwarder)). To
+//! be notified of changes to the child's terminal state such as flushes and
+//! flow control, see the [`packet`](crate::packet) module.
 //!
 //! # Features
 //!
 //! By default, only the [`blocking`](crate::blocking) APIs are available. To
 //! include the asynchronous APIs, you must enable the `async` feature.
+//!
+//! The `futures-io` feature (which implies `async`) additionally implements
+//! [`futures_io::AsyncRead`] and [`futures_io::AsyncWrite`] for
+//! [`Pty`](crate::Pty) and its split halves, for use with libraries built
+//! on the `futures::io` traits.
+//!
+//! The `mio` feature implements `mio::event::Source` for
+//! [`blocking::Pty`](crate::blocking::Pty), so that it can be registered
+//! with a hand-written `mio` event loop once it has been put into
+//! non-blocking mode via
+//! [`set_nonblocking`](crate::blocking::Pty::set_nonblocking).
+//!
+//! The `async-io` feature enables the [`async_io`](crate::async_io) module,
+//! which provides the same asynchronous APIs on top of `async-io` and
+//! `async-process` rather than tokio, implementing the `futures-io` traits,
+//! for use with runtimes such as `smol`.
+//!
+//! The `uring` feature enables the [`uring`](crate::uring) module (on
+//! Linux only), which provides a pty whose reads and writes are submitted
+//! via `io_uring` using `tokio-uring`.
+//!
+//! The `expect` feature enables the [`expect`](crate::expect) module (and
+//! [`blocking::expect`](crate::blocking::expect)), which provides support
+//! for automating interactive programs by waiting for their output to match
+//! given patterns.
+//!
+//! The `screen` feature enables the [`screen`](crate::screen) module (and
+//! [`blocking::screen`](crate::blocking::screen)), which provides a wrapper
+//! around the pty that keeps track of the terminal state (screen contents,
+//! cursor position, etc.) of the child process.
+//!
+//! The `asciicast` feature enables the [`asciicast`](crate::asciicast) module
+//! (and [`blocking::asciicast`](crate::blocking::asciicast)), which provides
+//! support for recording and replaying sessions in the asciicast v2 format.
+//!
+//! The `script` feature enables the [`script`](crate::script) module (and
+//! [`blocking::script`](crate::blocking::script)), which provides support
+//! for recording and replaying sessions in the typescript and timing file
+//! formats used by `script(1)` and `scriptreplay(1)`.
 
 #![warn(clippy::cargo)]
 #![warn(clippy::pedantic)]
@@ -48,6 +104,7 @@
 #![warn(clippy::get_unwrap)]
 #![allow(clippy::cognitive_complexity)]
 #![allow(clippy::missing_const_for_fn)]
+#![allow(clippy::multiple_crate_versions)]
 #![allow(clippy::similar_names)]
 #![allow(clippy::struct_excessive_bools)]
 #![allow(clippy::too_many_arguments)]
@@ -57,17 +114,50 @@
 mod error;
 pub use error::{Error, Result};
 mod types;
-pub use types::Size;
+/// Signals which can be sent to processes running in a pty
+pub use rustix::process::Signal;
+pub use types::{ControlChar, ParseSizeError, SetArg, Size, Termios};
 
+mod builder;
+mod resize;
+pub use resize::ResizeTarget;
 mod sys;
+mod transfer;
+pub use transfer::PtyMetadata;
 
+#[cfg(feature = "async-io")]
+pub mod async_io;
 pub mod blocking;
+pub mod packet;
+
+#[cfg(feature = "asciicast")]
+pub mod asciicast;
+#[cfg(feature = "expect")]
+pub mod expect;
+#[cfg(feature = "screen")]
+pub mod screen;
+#[cfg(feature = "script")]
+pub mod script;
+#[cfg(all(feature = "uring", target_os = "linux"))]
+pub mod uring;
 
+#[cfg(feature = "async")]
+pub use builder::PtyBuilder;
+#[cfg(feature = "async")]
+mod child;
+#[cfg(feature = "async")]
+pub use child::PtyChild;
 #[cfg(feature = "async")]
 mod command;
 #[cfg(feature = "async")]
 pub use command::Command;
 #[cfg(feature = "async")]
+mod interact;
+#[cfg(feature = "async")]
+pub use interact::interact;
+#[cfg(feature = "async")]
 mod pty;
 #[cfg(feature = "async")]
 pub use pty::{OwnedReadPty, OwnedWritePty, Pts, Pty, ReadPty, WritePty};
+#[cfg(feature = "async")]
+pub use resize::ResizeForwarder;
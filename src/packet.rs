@@ -0,0 +1,267 @@
+//! Decoding of output read from a pty in packet mode.
+//!
+//! When packet mode is enabled on a pty (see
+//! [`Pty::set_packet_mode`](crate::Pty::set_packet_mode)), every read from
+//! it returns a leading status byte. A status byte of zero means that the
+//! rest of the read contains ordinary output from the child, while any other
+//! value reports changes to the state of the child's terminal, such as the
+//! child flushing the terminal's queues with `tcflush(3)` or output being
+//! stopped and started with `^S` and `^Q`. This is primarily useful for
+//! remote terminal implementations which need to mirror these changes on the
+//! far side of the connection.
+//!
+//! A [`PacketReader`] wraps a pty in packet mode and decodes each read into
+//! a [`Packet`]. If `EXTPROC` is also enabled on the pty (see
+//! [`Termios::set_extproc`](crate::Termios::set_extproc)), the reader
+//! additionally reports whenever the child switches the terminal between
+//! canonical and raw input mode, which allows a remote client to do line
+//! editing locally while the child is reading whole lines:
+//!
+//! ```no_run
+//! # #[cfg(feature = "async")]
+//! # #[tokio::main]
+//! # async fn main() -> pty_process::Result<()> {
+//! let pty = pty_process::Pty::new()?;
+//! pty.set_packet_mode(true)?;
+//! let mut cmd = pty_process::Command::new("nethack");
+//! let child = cmd.spawn(&pty.pts()?)?;
+//! let mut reader = pty_process::packet::PacketReader::new(pty);
+//! while let Some(packet) = reader.read_packet().await? {
+//!     match packet {
+//!         pty_process::packet::Packet::Data(data) => {
+//!             println!("{} bytes of output", data.len());
+//!         }
+//!         pty_process::packet::Packet::Control(events) => {
+//!             println!("{events:?}");
+//!         }
+//!         pty_process::packet::Packet::ModeChanged(mode) => {
+//!             println!("child switched to {mode:?} mode");
+//!         }
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "async"))]
+//! # fn main() {}
+//! ```
+//!
+//! See [`blocking::packet`](crate::blocking::packet) for the blocking
+//! equivalent.
+
+// these values are the same on every platform which supports packet mode,
+// but libc doesn't define them everywhere
+const TIOCPKT_DATA: u8 = 0x00;
+const TIOCPKT_FLUSHREAD: u8 = 0x01;
+const TIOCPKT_FLUSHWRITE: u8 = 0x02;
+const TIOCPKT_STOP: u8 = 0x04;
+const TIOCPKT_START: u8 = 0x08;
+const TIOCPKT_NOSTOP: u8 = 0x10;
+const TIOCPKT_DOSTOP: u8 = 0x20;
+const TIOCPKT_IOCTL: u8 = 0x40;
+
+/// The size of the buffer used to read packets. The kernel never returns
+/// more than a single packet from one read, so this just limits how much
+/// output can be returned in a single [`Packet::Data`].
+pub(crate) const BUFFER_SIZE: usize = 4096;
+
+/// A single read from a pty in packet mode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Packet {
+    /// Output written by the child process
+    Data(Vec<u8>),
+    /// Changes to the state of the terminal. Several events may be reported
+    /// at once if they happened since the previous read.
+    Control(Vec<PacketEvent>),
+    /// The child switched the terminal between canonical and raw input
+    /// mode. This is not part of the data read from the pty, but is
+    /// reported by a [`PacketReader`] after a
+    /// [`PacketEvent::IoctlChanged`] event which changed the input mode.
+    ModeChanged(InputMode),
+}
+
+impl Packet {
+    /// Decodes the data returned by a single read from a pty in packet
+    /// mode, or returns `None` if the read returned end of file.
+    #[must_use]
+    pub fn decode(buf: &[u8]) -> Option<Self> {
+        let (&status, data) = buf.split_first()?;
+        if status == TIOCPKT_DATA {
+            return Some(Self::Data(data.to_vec()));
+        }
+        Some(Self::Control(
+            [
+                (TIOCPKT_FLUSHREAD, PacketEvent::FlushRead),
+                (TIOCPKT_FLUSHWRITE, PacketEvent::FlushWrite),
+                (TIOCPKT_STOP, PacketEvent::Stop),
+                (TIOCPKT_START, PacketEvent::Start),
+                (TIOCPKT_DOSTOP, PacketEvent::DoStop),
+                (TIOCPKT_NOSTOP, PacketEvent::NoStop),
+                (TIOCPKT_IOCTL, PacketEvent::IoctlChanged),
+            ]
+            .into_iter()
+            .filter(|(bit, _)| status & bit != 0)
+            .map(|(_, event)| event)
+            .collect(),
+        ))
+    }
+}
+
+/// A change to the state of a terminal, reported by a pty in packet mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PacketEvent {
+    /// The terminal's input queue was flushed (`TIOCPKT_FLUSHREAD`), so any
+    /// input which has been sent but not yet read by the child was
+    /// discarded.
+    FlushRead,
+    /// The terminal's output queue was flushed (`TIOCPKT_FLUSHWRITE`), so
+    /// any output which has been written by the child but not yet read from
+    /// the pty was discarded.
+    FlushWrite,
+    /// Output to the terminal was stopped (`TIOCPKT_STOP`), for instance by
+    /// `^S`.
+    Stop,
+    /// Output to the terminal was restarted (`TIOCPKT_START`), for instance
+    /// by `^Q`.
+    Start,
+    /// XON/XOFF flow control was enabled with the standard `^S` and `^Q`
+    /// characters (`TIOCPKT_DOSTOP`).
+    DoStop,
+    /// XON/XOFF flow control was disabled, or its characters were changed
+    /// from the standard `^S` and `^Q` (`TIOCPKT_NOSTOP`).
+    NoStop,
+    /// The terminal attributes were changed while `EXTPROC` was enabled
+    /// (`TIOCPKT_IOCTL`).
+    IoctlChanged,
+}
+
+/// The input mode of a terminal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputMode {
+    /// Input is assembled into lines by the terminal, and the child reads
+    /// it a line at a time (`ICANON` is set)
+    Canonical,
+    /// The child reads input as soon as it is typed (`ICANON` is not set)
+    Raw,
+}
+
+impl InputMode {
+    fn of(fd: std::os::fd::BorrowedFd<'_>) -> Option<Self> {
+        let termios = crate::Termios(rustix::termios::tcgetattr(fd).ok()?);
+        Some(if termios.canonical() {
+            Self::Canonical
+        } else {
+            Self::Raw
+        })
+    }
+}
+
+/// Keeps track of the input mode of a pty in packet mode, so that changes
+/// to it can be reported
+pub(crate) struct ModeTracker {
+    mode: Option<InputMode>,
+    pending: Option<Packet>,
+}
+
+impl ModeTracker {
+    pub(crate) fn new(fd: std::os::fd::BorrowedFd<'_>) -> Self {
+        Self {
+            mode: InputMode::of(fd),
+            pending: None,
+        }
+    }
+
+    /// Returns a [`Packet::ModeChanged`] which was queued by a previous
+    /// call to [`update`](Self::update), if any.
+    pub(crate) fn pending(&mut self) -> Option<Packet> {
+        self.pending.take()
+    }
+
+    /// Checks whether the given packet changed the input mode of the pty,
+    /// and if so, queues a [`Packet::ModeChanged`] to be returned next.
+    pub(crate) fn update(
+        &mut self,
+        fd: std::os::fd::BorrowedFd<'_>,
+        packet: &Packet,
+    ) {
+        let Packet::Control(events) = packet else {
+            return;
+        };
+        if !events.contains(&PacketEvent::IoctlChanged) {
+            return;
+        }
+        let Some(mode) = InputMode::of(fd) else {
+            return;
+        };
+        if self.mode != Some(mode) {
+            self.mode = Some(mode);
+            self.pending = Some(Packet::ModeChanged(mode));
+        }
+    }
+}
+
+/// Wrapper around a [`Pty`](crate::Pty) in packet mode (or either of its
+/// read halves) which decodes the data read from it into [`Packet`]s.
+///
+/// The wrapped reader must pass reads through to the pty directly (in
+/// particular, it must not be buffered), since each read from the pty
+/// returns exactly one packet.
+#[cfg(feature = "async")]
+pub struct PacketReader<R> {
+    reader: R,
+    buf: Vec<u8>,
+    modes: ModeTracker,
+}
+
+#[cfg(feature = "async")]
+impl<R> PacketReader<R>
+where
+    R: tokio::io::AsyncRead + std::os::fd::AsFd + std::marker::Unpin,
+{
+    /// Creates a new packet reader wrapping the given pty. Packet mode must
+    /// be enabled on the pty separately.
+    pub fn new(reader: R) -> Self {
+        let modes = ModeTracker::new(reader.as_fd());
+        Self {
+            reader,
+            buf: vec![0; BUFFER_SIZE],
+            modes,
+        }
+    }
+
+    /// Reads the next packet from the pty, or returns `None` once the pty
+    /// has been closed by every process holding the child end of it open.
+    ///
+    /// # Errors
+    /// Returns an error if reading from the pty failed.
+    pub async fn read_packet(&mut self) -> crate::Result<Option<Packet>> {
+        if let Some(packet) = self.modes.pending() {
+            return Ok(Some(packet));
+        }
+        let bytes =
+            tokio::io::AsyncReadExt::read(&mut self.reader, &mut self.buf)
+                .await?;
+        let packet = Packet::decode(&self.buf[..bytes]);
+        if let Some(packet) = &packet {
+            self.modes.update(self.reader.as_fd(), packet);
+        }
+        Ok(packet)
+    }
+
+    /// Returns a reference to the wrapped pty.
+    #[must_use]
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Returns a mutable reference to the wrapped pty. Reading from it
+    /// directly will return the raw packets, including the status byte.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Returns the wrapped pty.
+    #[must_use]
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
@@ -1,6 +1,8 @@
 #![allow(clippy::module_name_repetitions)]
+#![allow(clippy::needless_continue)]
+#![allow(clippy::elidable_lifetime_names)]
 
-use std::io::{Read as _, Write as _};
+use std::io::Write as _;
 
 type AsyncPty = tokio::io::unix::AsyncFd<crate::sys::Pty>;
 
@@ -14,7 +16,32 @@ impl Pty {
     /// Returns an error if the pty failed to be allocated, or if we were
     /// unable to put it into non-blocking mode.
     pub fn new() -> crate::Result<Self> {
-        let pty = crate::sys::Pty::open()?;
+        Self::from_sys(crate::sys::Pty::open()?)
+    }
+
+    /// Allocate and return a new pty from the given `ptmx` device node, such
+    /// as the `ptmx` node of a devpts instance mounted with `newinstance`
+    /// inside a container. The child end of the pty is opened from the
+    /// same devpts instance.
+    ///
+    /// # Errors
+    /// Returns an error if the device node could not be opened, or if it
+    /// is not a `ptmx` device, or if we were
+    /// unable to put it into non-blocking mode.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn open_at(ptmx: impl AsRef<std::path::Path>) -> crate::Result<Self> {
+        Self::from_sys(crate::sys::Pty::open_at(ptmx.as_ref())?)
+    }
+
+    /// Returns a builder which can be used to configure the size and
+    /// terminal attributes of a new pty before it is allocated. See
+    /// [`PtyBuilder`](crate::PtyBuilder).
+    #[must_use]
+    pub fn builder() -> crate::PtyBuilder {
+        crate::PtyBuilder::new()
+    }
+
+    pub(crate) fn from_sys(pty: crate::sys::Pty) -> crate::Result<Self> {
         pty.set_nonblocking()?;
         Ok(Self(tokio::io::unix::AsyncFd::new(pty)?))
     }
@@ -27,6 +54,158 @@ impl Pty {
         self.0.get_ref().set_term_size(size)
     }
 
+    /// Returns the terminal size currently associated with the pty.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to retrieve the terminal size.
+    pub fn size(&self) -> crate::Result<crate::Size> {
+        self.0.get_ref().term_size()
+    }
+
+    /// Returns the current terminal attributes of the pty.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to retrieve the terminal
+    /// attributes.
+    pub fn termios(&self) -> crate::Result<crate::Termios> {
+        self.0.get_ref().termios()
+    }
+
+    /// Change the terminal attributes of the pty. See [`crate::SetArg`] for
+    /// the meaning of `when`.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to set the terminal attributes.
+    pub fn set_termios(
+        &self,
+        when: crate::SetArg,
+        termios: &crate::Termios,
+    ) -> crate::Result<()> {
+        self.0.get_ref().set_termios(when, termios)
+    }
+
+    /// Returns the id of the foreground process group of the pty. This is
+    /// the process group which receives signals generated by the terminal
+    /// (such as `SIGINT` when `^C` is typed), and may be different from the
+    /// process group of the child process if the child is a shell which has
+    /// started a job.
+    ///
+    /// # Errors
+    /// Returns an error if the foreground process group could not be
+    /// determined.
+    pub fn foreground_process_group(&self) -> crate::Result<u32> {
+        self.0.get_ref().foreground_process_group()
+    }
+
+    /// Sends the given signal to the foreground process group of the pty
+    /// (see [`foreground_process_group`](Self::foreground_process_group)).
+    ///
+    /// # Errors
+    /// Returns an error if the foreground process group could not be
+    /// determined, or if the signal could not be sent.
+    pub fn signal_foreground(
+        &self,
+        signal: crate::Signal,
+    ) -> crate::Result<()> {
+        self.0.get_ref().signal_foreground(signal)
+    }
+
+    /// Returns the id of the session which the pty is the controlling
+    /// terminal of.
+    ///
+    /// # Errors
+    /// Returns an error if the pty is not the controlling terminal of any
+    /// session.
+    pub fn session_id(&self) -> crate::Result<u32> {
+        self.0.get_ref().session_id()
+    }
+
+    /// Enables or disables packet mode on the pty. In packet mode, every
+    /// read from the pty returns a leading status byte, which reports
+    /// events such as the child flushing the terminal's queues or changing
+    /// its flow control settings. Use a
+    /// [`PacketReader`](crate::packet::PacketReader) to decode the data
+    /// read from a pty in packet mode.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to change the packet mode.
+    pub fn set_packet_mode(&self, enabled: bool) -> crate::Result<()> {
+        self.0.get_ref().set_packet_mode(enabled)
+    }
+
+    /// Sends the interrupt character (`VINTR`, usually `^C`) to the pty,
+    /// which will send `SIGINT` to the foreground process group if
+    /// `ISIG` is enabled. The character is looked up in the current terminal
+    /// attributes of the pty, so this works even if it has been remapped.
+    ///
+    /// # Errors
+    /// Returns an error if the terminal attributes could not be retrieved,
+    /// if the character is disabled, or if writing to the pty failed.
+    pub async fn send_interrupt(&mut self) -> crate::Result<()> {
+        self.send_control_char(crate::ControlChar::Intr).await
+    }
+
+    /// Sends the quit character (`VQUIT`, usually `^\`) to the pty,
+    /// which will send `SIGQUIT` to the foreground process group if
+    /// `ISIG` is enabled. The character is looked up in the current terminal
+    /// attributes of the pty, so this works even if it has been remapped.
+    ///
+    /// # Errors
+    /// Returns an error if the terminal attributes could not be retrieved,
+    /// if the character is disabled, or if writing to the pty failed.
+    pub async fn send_quit(&mut self) -> crate::Result<()> {
+        self.send_control_char(crate::ControlChar::Quit).await
+    }
+
+    /// Sends the suspend character (`VSUSP`, usually `^Z`) to the pty,
+    /// which will send `SIGTSTP` to the foreground process group if
+    /// `ISIG` is enabled. The character is looked up in the current terminal
+    /// attributes of the pty, so this works even if it has been remapped.
+    ///
+    /// # Errors
+    /// Returns an error if the terminal attributes could not be retrieved,
+    /// if the character is disabled, or if writing to the pty failed.
+    pub async fn send_suspend(&mut self) -> crate::Result<()> {
+        self.send_control_char(crate::ControlChar::Susp).await
+    }
+
+    /// Sends the end of file character (`VEOF`, usually `^D`) to the pty,
+    /// which will cause the child's next read to return end of file if
+    /// the pty is in canonical mode. If there is unfinished input on the
+    /// current line, the character instead just sends that input to the
+    /// child without a trailing newline, and this must be called a second
+    /// time to signal end of file. The character is looked up in the
+    /// current terminal attributes of the pty, so this works even if it has
+    /// been remapped.
+    ///
+    /// # Errors
+    /// Returns an error if the terminal attributes could not be retrieved,
+    /// if the character is disabled, or if writing to the pty failed.
+    pub async fn send_eof(&mut self) -> crate::Result<()> {
+        self.send_control_char(crate::ControlChar::Eof).await
+    }
+
+    /// Sends the erase character (`VERASE`, usually `^?`) to the pty,
+    /// which will erase the previous character of input if the pty is in
+    /// canonical mode. The character is looked up in the current terminal
+    /// attributes of the pty, so this works even if it has been remapped.
+    ///
+    /// # Errors
+    /// Returns an error if the terminal attributes could not be retrieved,
+    /// if the character is disabled, or if writing to the pty failed.
+    pub async fn send_erase(&mut self) -> crate::Result<()> {
+        self.send_control_char(crate::ControlChar::Erase).await
+    }
+
+    async fn send_control_char(
+        &mut self,
+        cc: crate::ControlChar,
+    ) -> crate::Result<()> {
+        let byte = self.0.get_ref().control_char(cc)?;
+        tokio::io::AsyncWriteExt::write_all(self, &[byte]).await?;
+        Ok(())
+    }
+
     /// Opens a file descriptor for the other end of the pty, which should be
     /// attached to the child process running in it. See
     /// [`Command::spawn`](crate::Command::spawn).
@@ -38,9 +217,110 @@ impl Pty {
         Ok(Pts(self.0.get_ref().pts()?))
     }
 
+    /// Returns the index of the pty, as used in the name of its device node
+    /// (for instance, `3` for `/dev/pts/3`). This is mainly useful for
+    /// logging.
+    ///
+    /// # Errors
+    /// Returns an error if the index could not be determined.
+    pub fn pts_index(&self) -> crate::Result<u32> {
+        self.0.get_ref().pts_index()
+    }
+
+    /// Reads all remaining output from the pty until every process holding
+    /// the child end of the pty open has closed it (typically, when the
+    /// child process and any other processes in its session have exited),
+    /// and returns it.
+    ///
+    /// Note that this will never return if the [`Pts`] used to spawn the
+    /// child is still open.
+    ///
+    /// # Errors
+    /// Returns an error if reading from the pty failed.
+    pub async fn drain_until_hangup(&mut self) -> crate::Result<Vec<u8>> {
+        let mut output = vec![];
+        tokio::io::AsyncReadExt::read_to_end(self, &mut output).await?;
+        Ok(output)
+    }
+
+    /// Creates a pty from a file descriptor for the parent end of an existing
+    /// pty, such as one received from another process. The file descriptor
+    /// is put into non-blocking mode (note that this also affects any other
+    /// file descriptors for the same pty, including in other processes).
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to put the file descriptor into
+    /// non-blocking mode, or to register it with the tokio runtime.
+    pub fn from_fd(fd: std::os::fd::OwnedFd) -> crate::Result<Self> {
+        Self::from_sys(crate::sys::Pty::from_fd(fd))
+    }
+
+    /// Returns the current state of the pty, to be sent along with it via
+    /// [`send_to`](Self::send_to).
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to retrieve the terminal size,
+    /// the terminal attributes, or the index of the pty.
+    pub fn metadata(&self) -> crate::Result<crate::PtyMetadata> {
+        crate::PtyMetadata::new(self.0.get_ref())
+    }
+
+    /// Sends the pty to another process over a unix socket (as
+    /// `SCM_RIGHTS` ancillary data), along with the given metadata. The
+    /// other process can receive it with [`recv_from`](Self::recv_from) (or
+    /// [`blocking::Pty::recv_from`](crate::blocking::Pty::recv_from)).
+    ///
+    /// The pty remains open in this process until it is dropped, which
+    /// should typically happen once it has been sent, since both processes
+    /// reading from the same pty will result in each one seeing only part of
+    /// the output.
+    ///
+    /// # Errors
+    /// Returns an error if sending to the socket failed.
+    pub async fn send_to(
+        &self,
+        stream: &tokio::net::UnixStream,
+        metadata: &crate::PtyMetadata,
+    ) -> crate::Result<()> {
+        stream
+            .async_io(tokio::io::Interest::WRITABLE, || {
+                crate::transfer::send(
+                    std::os::fd::AsFd::as_fd(stream),
+                    self.0.get_ref(),
+                    metadata,
+                )
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Receives a pty sent by [`send_to`](Self::send_to) (or
+    /// [`blocking::Pty::send_to`](crate::blocking::Pty::send_to)) from a
+    /// unix socket, along with the metadata it was sent with. The pty is
+    /// set up as with [`from_fd`](Self::from_fd).
+    ///
+    /// # Errors
+    /// Returns an error if receiving from the socket failed, if the data
+    /// received was not sent by `send_to`, or if setting up the pty failed.
+    pub async fn recv_from(
+        stream: &tokio::net::UnixStream,
+    ) -> crate::Result<(Self, crate::PtyMetadata)> {
+        let (pty, metadata) = stream
+            .async_io(tokio::io::Interest::READABLE, || {
+                crate::transfer::recv(std::os::fd::AsFd::as_fd(stream))
+            })
+            .await?;
+        Ok((Self::from_sys(pty)?, metadata))
+    }
+
     /// Splits a `Pty` into a read half and a write half, which can be used to
     /// read from and write to the pty concurrently. Does not allocate, but
     /// the returned halves cannot be moved to independent tasks.
+    ///
+    /// Note that with the `futures-io` feature, if
+    /// `futures::io::AsyncReadExt` is in scope, `pty.split()` will call its
+    /// `split` method instead, so this needs to be called as
+    /// `Pty::split(&mut pty)`.
     pub fn split(&mut self) -> (ReadPty<'_>, WritePty<'_>) {
         (ReadPty(&self.0), WritePty(&self.0))
     }
@@ -83,23 +363,7 @@ impl tokio::io::AsyncRead for Pty {
         cx: &mut std::task::Context<'_>,
         buf: &mut tokio::io::ReadBuf,
     ) -> std::task::Poll<std::io::Result<()>> {
-        loop {
-            let mut guard = match self.0.poll_read_ready(cx) {
-                std::task::Poll::Ready(guard) => guard,
-                std::task::Poll::Pending => return std::task::Poll::Pending,
-            }?;
-            // XXX should be able to optimize this once read_buf is stabilized
-            // in std
-            let b = buf.initialize_unfilled();
-            match guard.try_io(|inner| inner.get_ref().read(b)) {
-                Ok(Ok(bytes)) => {
-                    buf.advance(bytes);
-                    return std::task::Poll::Ready(Ok(()));
-                }
-                Ok(Err(e)) => return std::task::Poll::Ready(Err(e)),
-                Err(_would_block) => continue,
-            }
-        }
+        poll_read(&self.0, cx, buf)
     }
 }
 
@@ -109,32 +373,26 @@ impl tokio::io::AsyncWrite for Pty {
         cx: &mut std::task::Context<'_>,
         buf: &[u8],
     ) -> std::task::Poll<std::io::Result<usize>> {
-        loop {
-            let mut guard = match self.0.poll_write_ready(cx) {
-                std::task::Poll::Ready(guard) => guard,
-                std::task::Poll::Pending => return std::task::Poll::Pending,
-            }?;
-            match guard.try_io(|inner| inner.get_ref().write(buf)) {
-                Ok(result) => return std::task::Poll::Ready(result),
-                Err(_would_block) => continue,
-            }
-        }
+        poll_write(&self.0, cx, buf)
+    }
+
+    fn poll_write_vectored(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        bufs: &[std::io::IoSlice<'_>],
+    ) -> std::task::Poll<std::io::Result<usize>> {
+        poll_write_vectored(&self.0, cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        true
     }
 
     fn poll_flush(
         self: std::pin::Pin<&mut Self>,
         cx: &mut std::task::Context<'_>,
     ) -> std::task::Poll<std::io::Result<()>> {
-        loop {
-            let mut guard = match self.0.poll_write_ready(cx) {
-                std::task::Poll::Ready(guard) => guard,
-                std::task::Poll::Pending => return std::task::Poll::Pending,
-            }?;
-            match guard.try_io(|inner| inner.get_ref().flush()) {
-                Ok(_) => return std::task::Poll::Ready(Ok(())),
-                Err(_would_block) => continue,
-            }
-        }
+        poll_flush(&self.0, cx)
     }
 
     fn poll_shutdown(
@@ -150,32 +408,66 @@ impl tokio::io::AsyncWrite for Pty {
 /// See [`Pty::pts`] and [`Command::spawn`](crate::Command::spawn)
 pub struct Pts(pub(crate) crate::sys::Pts);
 
+impl Pts {
+    /// Returns the terminal size as seen from the child end of the pty.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to retrieve the terminal size.
+    pub fn size(&self) -> crate::Result<crate::Size> {
+        self.0.term_size()
+    }
+
+    /// Returns the path of the device node for the child end of the pty
+    /// (for instance, `/dev/pts/3`). This is intended for display purposes
+    /// only: the pty is not opened through this path, and it may not refer
+    /// to the same device if the pty was allocated in a different mount
+    /// namespace.
+    #[must_use]
+    pub fn path(&self) -> &std::path::Path {
+        self.0.path()
+    }
+}
+
 /// Borrowed read half of a [`Pty`]
 pub struct ReadPty<'a>(&'a AsyncPty);
 
+impl<'a> ReadPty<'a> {
+    /// Reads all remaining output from the pty until every process holding
+    /// the child end of the pty open has closed it (typically, when the
+    /// child process and any other processes in its session have exited),
+    /// and returns it.
+    ///
+    /// Note that this will never return if the [`Pts`] used to spawn the
+    /// child is still open.
+    ///
+    /// # Errors
+    /// Returns an error if reading from the pty failed.
+    pub async fn drain_until_hangup(&mut self) -> crate::Result<Vec<u8>> {
+        let mut output = vec![];
+        tokio::io::AsyncReadExt::read_to_end(self, &mut output).await?;
+        Ok(output)
+    }
+}
+
+impl<'a> std::os::fd::AsFd for ReadPty<'a> {
+    fn as_fd(&self) -> std::os::fd::BorrowedFd<'_> {
+        self.0.get_ref().as_fd()
+    }
+}
+
+impl<'a> std::os::fd::AsRawFd for ReadPty<'a> {
+    fn as_raw_fd(&self) -> std::os::fd::RawFd {
+        self.0.get_ref().as_raw_fd()
+    }
+}
+
 impl<'a> tokio::io::AsyncRead for ReadPty<'a> {
     fn poll_read(
         self: std::pin::Pin<&mut Self>,
         cx: &mut std::task::Context<'_>,
         buf: &mut tokio::io::ReadBuf,
     ) -> std::task::Poll<std::io::Result<()>> {
-        loop {
-            let mut guard = match self.0.poll_read_ready(cx) {
-                std::task::Poll::Ready(guard) => guard,
-                std::task::Poll::Pending => return std::task::Poll::Pending,
-            }?;
-            // XXX should be able to optimize this once read_buf is stabilized
-            // in std
-            let b = buf.initialize_unfilled();
-            match guard.try_io(|inner| inner.get_ref().read(b)) {
-                Ok(Ok(bytes)) => {
-                    buf.advance(bytes);
-                    return std::task::Poll::Ready(Ok(()));
-                }
-                Ok(Err(e)) => return std::task::Poll::Ready(Err(e)),
-                Err(_would_block) => continue,
-            }
-        }
+        poll_read(self.0, cx, buf)
     }
 }
 
@@ -190,6 +482,158 @@ impl<'a> WritePty<'a> {
     pub fn resize(&self, size: crate::Size) -> crate::Result<()> {
         self.0.get_ref().set_term_size(size)
     }
+
+    /// Returns the terminal size currently associated with the pty.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to retrieve the terminal size.
+    pub fn size(&self) -> crate::Result<crate::Size> {
+        self.0.get_ref().term_size()
+    }
+
+    /// Returns the current terminal attributes of the pty.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to retrieve the terminal
+    /// attributes.
+    pub fn termios(&self) -> crate::Result<crate::Termios> {
+        self.0.get_ref().termios()
+    }
+
+    /// Change the terminal attributes of the pty. See [`crate::SetArg`] for
+    /// the meaning of `when`.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to set the terminal attributes.
+    pub fn set_termios(
+        &self,
+        when: crate::SetArg,
+        termios: &crate::Termios,
+    ) -> crate::Result<()> {
+        self.0.get_ref().set_termios(when, termios)
+    }
+
+    /// Returns the id of the foreground process group of the pty. This is
+    /// the process group which receives signals generated by the terminal
+    /// (such as `SIGINT` when `^C` is typed), and may be different from the
+    /// process group of the child process if the child is a shell which has
+    /// started a job.
+    ///
+    /// # Errors
+    /// Returns an error if the foreground process group could not be
+    /// determined.
+    pub fn foreground_process_group(&self) -> crate::Result<u32> {
+        self.0.get_ref().foreground_process_group()
+    }
+
+    /// Sends the given signal to the foreground process group of the pty
+    /// (see [`foreground_process_group`](Self::foreground_process_group)).
+    ///
+    /// # Errors
+    /// Returns an error if the foreground process group could not be
+    /// determined, or if the signal could not be sent.
+    pub fn signal_foreground(
+        &self,
+        signal: crate::Signal,
+    ) -> crate::Result<()> {
+        self.0.get_ref().signal_foreground(signal)
+    }
+
+    /// Returns the id of the session which the pty is the controlling
+    /// terminal of.
+    ///
+    /// # Errors
+    /// Returns an error if the pty is not the controlling terminal of any
+    /// session.
+    pub fn session_id(&self) -> crate::Result<u32> {
+        self.0.get_ref().session_id()
+    }
+
+    /// Enables or disables packet mode on the pty. In packet mode, every
+    /// read from the pty returns a leading status byte, which reports
+    /// events such as the child flushing the terminal's queues or changing
+    /// its flow control settings. Use a
+    /// [`PacketReader`](crate::packet::PacketReader) to decode the data
+    /// read from a pty in packet mode.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to change the packet mode.
+    pub fn set_packet_mode(&self, enabled: bool) -> crate::Result<()> {
+        self.0.get_ref().set_packet_mode(enabled)
+    }
+
+    /// Sends the interrupt character (`VINTR`, usually `^C`) to the pty,
+    /// which will send `SIGINT` to the foreground process group if
+    /// `ISIG` is enabled. The character is looked up in the current terminal
+    /// attributes of the pty, so this works even if it has been remapped.
+    ///
+    /// # Errors
+    /// Returns an error if the terminal attributes could not be retrieved,
+    /// if the character is disabled, or if writing to the pty failed.
+    pub async fn send_interrupt(&mut self) -> crate::Result<()> {
+        self.send_control_char(crate::ControlChar::Intr).await
+    }
+
+    /// Sends the quit character (`VQUIT`, usually `^\`) to the pty,
+    /// which will send `SIGQUIT` to the foreground process group if
+    /// `ISIG` is enabled. The character is looked up in the current terminal
+    /// attributes of the pty, so this works even if it has been remapped.
+    ///
+    /// # Errors
+    /// Returns an error if the terminal attributes could not be retrieved,
+    /// if the character is disabled, or if writing to the pty failed.
+    pub async fn send_quit(&mut self) -> crate::Result<()> {
+        self.send_control_char(crate::ControlChar::Quit).await
+    }
+
+    /// Sends the suspend character (`VSUSP`, usually `^Z`) to the pty,
+    /// which will send `SIGTSTP` to the foreground process group if
+    /// `ISIG` is enabled. The character is looked up in the current terminal
+    /// attributes of the pty, so this works even if it has been remapped.
+    ///
+    /// # Errors
+    /// Returns an error if the terminal attributes could not be retrieved,
+    /// if the character is disabled, or if writing to the pty failed.
+    pub async fn send_suspend(&mut self) -> crate::Result<()> {
+        self.send_control_char(crate::ControlChar::Susp).await
+    }
+
+    /// Sends the end of file character (`VEOF`, usually `^D`) to the pty,
+    /// which will cause the child's next read to return end of file if
+    /// the pty is in canonical mode. If there is unfinished input on the
+    /// current line, the character instead just sends that input to the
+    /// child without a trailing newline, and this must be called a second
+    /// time to signal end of file. The character is looked up in the
+    /// current terminal attributes of the pty, so this works even if it has
+    /// been remapped.
+    ///
+    /// # Errors
+    /// Returns an error if the terminal attributes could not be retrieved,
+    /// if the character is disabled, or if writing to the pty failed.
+    pub async fn send_eof(&mut self) -> crate::Result<()> {
+        self.send_control_char(crate::ControlChar::Eof).await
+    }
+
+    /// Sends the erase character (`VERASE`, usually `^?`) to the pty,
+    /// which will erase the previous character of input if the pty is in
+    /// canonical mode. The character is looked up in the current terminal
+    /// attributes of the pty, so this works even if it has been remapped.
+    ///
+    /// # Errors
+    /// Returns an error if the terminal attributes could not be retrieved,
+    /// if the character is disabled, or if writing to the pty failed.
+    pub async fn send_erase(&mut self) -> crate::Result<()> {
+        self.send_control_char(crate::ControlChar::Erase).await
+    }
+
+    async fn send_control_char(
+        &mut self,
+        cc: crate::ControlChar,
+    ) -> crate::Result<()> {
+        let byte = self.0.get_ref().control_char(cc)?;
+        tokio::io::AsyncWriteExt::write_all(self, &[byte]).await?;
+        Ok(())
+    }
 }
 
 impl<'a> tokio::io::AsyncWrite for WritePty<'a> {
@@ -198,32 +642,26 @@ impl<'a> tokio::io::AsyncWrite for WritePty<'a> {
         cx: &mut std::task::Context<'_>,
         buf: &[u8],
     ) -> std::task::Poll<std::io::Result<usize>> {
-        loop {
-            let mut guard = match self.0.poll_write_ready(cx) {
-                std::task::Poll::Ready(guard) => guard,
-                std::task::Poll::Pending => return std::task::Poll::Pending,
-            }?;
-            match guard.try_io(|inner| inner.get_ref().write(buf)) {
-                Ok(result) => return std::task::Poll::Ready(result),
-                Err(_would_block) => continue,
-            }
-        }
+        poll_write(self.0, cx, buf)
+    }
+
+    fn poll_write_vectored(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        bufs: &[std::io::IoSlice<'_>],
+    ) -> std::task::Poll<std::io::Result<usize>> {
+        poll_write_vectored(self.0, cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        true
     }
 
     fn poll_flush(
         self: std::pin::Pin<&mut Self>,
         cx: &mut std::task::Context<'_>,
     ) -> std::task::Poll<std::io::Result<()>> {
-        loop {
-            let mut guard = match self.0.poll_write_ready(cx) {
-                std::task::Poll::Ready(guard) => guard,
-                std::task::Poll::Pending => return std::task::Poll::Pending,
-            }?;
-            match guard.try_io(|inner| inner.get_ref().flush()) {
-                Ok(_) => return std::task::Poll::Ready(Ok(())),
-                Err(_would_block) => continue,
-            }
-        }
+        poll_flush(self.0, cx)
     }
 
     fn poll_shutdown(
@@ -239,6 +677,22 @@ impl<'a> tokio::io::AsyncWrite for WritePty<'a> {
 pub struct OwnedReadPty(std::sync::Arc<AsyncPty>);
 
 impl OwnedReadPty {
+    /// Reads all remaining output from the pty until every process holding
+    /// the child end of the pty open has closed it (typically, when the
+    /// child process and any other processes in its session have exited),
+    /// and returns it.
+    ///
+    /// Note that this will never return if the [`Pts`] used to spawn the
+    /// child is still open.
+    ///
+    /// # Errors
+    /// Returns an error if reading from the pty failed.
+    pub async fn drain_until_hangup(&mut self) -> crate::Result<Vec<u8>> {
+        let mut output = vec![];
+        tokio::io::AsyncReadExt::read_to_end(self, &mut output).await?;
+        Ok(output)
+    }
+
     /// Attempt to join the two halves of a `Pty` back into a single instance.
     /// The two halves must have originated from calling
     /// [`into_split`](Pty::into_split) on a single instance.
@@ -264,29 +718,25 @@ impl OwnedReadPty {
     }
 }
 
+impl std::os::fd::AsFd for OwnedReadPty {
+    fn as_fd(&self) -> std::os::fd::BorrowedFd<'_> {
+        self.0.get_ref().as_fd()
+    }
+}
+
+impl std::os::fd::AsRawFd for OwnedReadPty {
+    fn as_raw_fd(&self) -> std::os::fd::RawFd {
+        self.0.get_ref().as_raw_fd()
+    }
+}
+
 impl tokio::io::AsyncRead for OwnedReadPty {
     fn poll_read(
         self: std::pin::Pin<&mut Self>,
         cx: &mut std::task::Context<'_>,
         buf: &mut tokio::io::ReadBuf,
     ) -> std::task::Poll<std::io::Result<()>> {
-        loop {
-            let mut guard = match self.0.poll_read_ready(cx) {
-                std::task::Poll::Ready(guard) => guard,
-                std::task::Poll::Pending => return std::task::Poll::Pending,
-            }?;
-            // XXX should be able to optimize this once read_buf is stabilized
-            // in std
-            let b = buf.initialize_unfilled();
-            match guard.try_io(|inner| inner.get_ref().read(b)) {
-                Ok(Ok(bytes)) => {
-                    buf.advance(bytes);
-                    return std::task::Poll::Ready(Ok(()));
-                }
-                Ok(Err(e)) => return std::task::Poll::Ready(Err(e)),
-                Err(_would_block) => continue,
-            }
-        }
+        poll_read(&self.0, cx, buf)
     }
 }
 
@@ -302,6 +752,170 @@ impl OwnedWritePty {
     pub fn resize(&self, size: crate::Size) -> crate::Result<()> {
         self.0.get_ref().set_term_size(size)
     }
+
+    /// Returns the terminal size currently associated with the pty.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to retrieve the terminal size.
+    pub fn size(&self) -> crate::Result<crate::Size> {
+        self.0.get_ref().term_size()
+    }
+
+    /// Returns the current terminal attributes of the pty.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to retrieve the terminal
+    /// attributes.
+    pub fn termios(&self) -> crate::Result<crate::Termios> {
+        self.0.get_ref().termios()
+    }
+
+    /// Change the terminal attributes of the pty. See [`crate::SetArg`] for
+    /// the meaning of `when`.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to set the terminal attributes.
+    pub fn set_termios(
+        &self,
+        when: crate::SetArg,
+        termios: &crate::Termios,
+    ) -> crate::Result<()> {
+        self.0.get_ref().set_termios(when, termios)
+    }
+
+    /// Returns the id of the foreground process group of the pty. This is
+    /// the process group which receives signals generated by the terminal
+    /// (such as `SIGINT` when `^C` is typed), and may be different from the
+    /// process group of the child process if the child is a shell which has
+    /// started a job.
+    ///
+    /// # Errors
+    /// Returns an error if the foreground process group could not be
+    /// determined.
+    pub fn foreground_process_group(&self) -> crate::Result<u32> {
+        self.0.get_ref().foreground_process_group()
+    }
+
+    /// Sends the given signal to the foreground process group of the pty
+    /// (see [`foreground_process_group`](Self::foreground_process_group)).
+    ///
+    /// # Errors
+    /// Returns an error if the foreground process group could not be
+    /// determined, or if the signal could not be sent.
+    pub fn signal_foreground(
+        &self,
+        signal: crate::Signal,
+    ) -> crate::Result<()> {
+        self.0.get_ref().signal_foreground(signal)
+    }
+
+    /// Returns the id of the session which the pty is the controlling
+    /// terminal of.
+    ///
+    /// # Errors
+    /// Returns an error if the pty is not the controlling terminal of any
+    /// session.
+    pub fn session_id(&self) -> crate::Result<u32> {
+        self.0.get_ref().session_id()
+    }
+
+    /// Enables or disables packet mode on the pty. In packet mode, every
+    /// read from the pty returns a leading status byte, which reports
+    /// events such as the child flushing the terminal's queues or changing
+    /// its flow control settings. Use a
+    /// [`PacketReader`](crate::packet::PacketReader) to decode the data
+    /// read from a pty in packet mode.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to change the packet mode.
+    pub fn set_packet_mode(&self, enabled: bool) -> crate::Result<()> {
+        self.0.get_ref().set_packet_mode(enabled)
+    }
+
+    /// Sends the interrupt character (`VINTR`, usually `^C`) to the pty,
+    /// which will send `SIGINT` to the foreground process group if
+    /// `ISIG` is enabled. The character is looked up in the current terminal
+    /// attributes of the pty, so this works even if it has been remapped.
+    ///
+    /// # Errors
+    /// Returns an error if the terminal attributes could not be retrieved,
+    /// if the character is disabled, or if writing to the pty failed.
+    pub async fn send_interrupt(&mut self) -> crate::Result<()> {
+        self.send_control_char(crate::ControlChar::Intr).await
+    }
+
+    /// Sends the quit character (`VQUIT`, usually `^\`) to the pty,
+    /// which will send `SIGQUIT` to the foreground process group if
+    /// `ISIG` is enabled. The character is looked up in the current terminal
+    /// attributes of the pty, so this works even if it has been remapped.
+    ///
+    /// # Errors
+    /// Returns an error if the terminal attributes could not be retrieved,
+    /// if the character is disabled, or if writing to the pty failed.
+    pub async fn send_quit(&mut self) -> crate::Result<()> {
+        self.send_control_char(crate::ControlChar::Quit).await
+    }
+
+    /// Sends the suspend character (`VSUSP`, usually `^Z`) to the pty,
+    /// which will send `SIGTSTP` to the foreground process group if
+    /// `ISIG` is enabled. The character is looked up in the current terminal
+    /// attributes of the pty, so this works even if it has been remapped.
+    ///
+    /// # Errors
+    /// Returns an error if the terminal attributes could not be retrieved,
+    /// if the character is disabled, or if writing to the pty failed.
+    pub async fn send_suspend(&mut self) -> crate::Result<()> {
+        self.send_control_char(crate::ControlChar::Susp).await
+    }
+
+    /// Sends the end of file character (`VEOF`, usually `^D`) to the pty,
+    /// which will cause the child's next read to return end of file if
+    /// the pty is in canonical mode. If there is unfinished input on the
+    /// current line, the character instead just sends that input to the
+    /// child without a trailing newline, and this must be called a second
+    /// time to signal end of file. The character is looked up in the
+    /// current terminal attributes of the pty, so this works even if it has
+    /// been remapped.
+    ///
+    /// # Errors
+    /// Returns an error if the terminal attributes could not be retrieved,
+    /// if the character is disabled, or if writing to the pty failed.
+    pub async fn send_eof(&mut self) -> crate::Result<()> {
+        self.send_control_char(crate::ControlChar::Eof).await
+    }
+
+    /// Sends the erase character (`VERASE`, usually `^?`) to the pty,
+    /// which will erase the previous character of input if the pty is in
+    /// canonical mode. The character is looked up in the current terminal
+    /// attributes of the pty, so this works even if it has been remapped.
+    ///
+    /// # Errors
+    /// Returns an error if the terminal attributes could not be retrieved,
+    /// if the character is disabled, or if writing to the pty failed.
+    pub async fn send_erase(&mut self) -> crate::Result<()> {
+        self.send_control_char(crate::ControlChar::Erase).await
+    }
+
+    async fn send_control_char(
+        &mut self,
+        cc: crate::ControlChar,
+    ) -> crate::Result<()> {
+        let byte = self.0.get_ref().control_char(cc)?;
+        tokio::io::AsyncWriteExt::write_all(self, &[byte]).await?;
+        Ok(())
+    }
+}
+
+impl std::os::fd::AsFd for OwnedWritePty {
+    fn as_fd(&self) -> std::os::fd::BorrowedFd<'_> {
+        self.0.get_ref().as_fd()
+    }
+}
+
+impl std::os::fd::AsRawFd for OwnedWritePty {
+    fn as_raw_fd(&self) -> std::os::fd::RawFd {
+        self.0.get_ref().as_raw_fd()
+    }
 }
 
 impl tokio::io::AsyncWrite for OwnedWritePty {
@@ -310,32 +924,26 @@ impl tokio::io::AsyncWrite for OwnedWritePty {
         cx: &mut std::task::Context<'_>,
         buf: &[u8],
     ) -> std::task::Poll<std::io::Result<usize>> {
-        loop {
-            let mut guard = match self.0.poll_write_ready(cx) {
-                std::task::Poll::Ready(guard) => guard,
-                std::task::Poll::Pending => return std::task::Poll::Pending,
-            }?;
-            match guard.try_io(|inner| inner.get_ref().write(buf)) {
-                Ok(result) => return std::task::Poll::Ready(result),
-                Err(_would_block) => continue,
-            }
-        }
+        poll_write(&self.0, cx, buf)
+    }
+
+    fn poll_write_vectored(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        bufs: &[std::io::IoSlice<'_>],
+    ) -> std::task::Poll<std::io::Result<usize>> {
+        poll_write_vectored(&self.0, cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        true
     }
 
     fn poll_flush(
         self: std::pin::Pin<&mut Self>,
         cx: &mut std::task::Context<'_>,
     ) -> std::task::Poll<std::io::Result<()>> {
-        loop {
-            let mut guard = match self.0.poll_write_ready(cx) {
-                std::task::Poll::Ready(guard) => guard,
-                std::task::Poll::Pending => return std::task::Poll::Pending,
-            }?;
-            match guard.try_io(|inner| inner.get_ref().flush()) {
-                Ok(_) => return std::task::Poll::Ready(Ok(())),
-                Err(_would_block) => continue,
-            }
-        }
+        poll_flush(&self.0, cx)
     }
 
     fn poll_shutdown(
@@ -345,3 +953,230 @@ impl tokio::io::AsyncWrite for OwnedWritePty {
         std::task::Poll::Ready(Ok(()))
     }
 }
+
+fn poll_read(
+    pty: &AsyncPty,
+    cx: &mut std::task::Context<'_>,
+    buf: &mut tokio::io::ReadBuf,
+) -> std::task::Poll<std::io::Result<()>> {
+    loop {
+        let mut guard = match pty.poll_read_ready(cx) {
+            std::task::Poll::Ready(guard) => guard,
+            std::task::Poll::Pending => return std::task::Poll::Pending,
+        }?;
+        // Safety: read_uninit only ever writes initialized bytes into the
+        // buffer, so nothing that was previously initialized is lost
+        let unfilled = unsafe { buf.unfilled_mut() };
+        match guard.try_io(|inner| inner.get_ref().read_uninit(unfilled)) {
+            Ok(Ok(bytes)) => {
+                // Safety: read_uninit returns the number of bytes at the
+                // start of the unfilled part of the buffer that it wrote to
+                unsafe { buf.assume_init(bytes) };
+                buf.advance(bytes);
+                return std::task::Poll::Ready(Ok(()));
+            }
+            Ok(Err(e)) => return std::task::Poll::Ready(Err(e)),
+            Err(_would_block) => continue,
+        }
+    }
+}
+
+fn poll_write(
+    pty: &AsyncPty,
+    cx: &mut std::task::Context<'_>,
+    buf: &[u8],
+) -> std::task::Poll<std::io::Result<usize>> {
+    loop {
+        let mut guard = match pty.poll_write_ready(cx) {
+            std::task::Poll::Ready(guard) => guard,
+            std::task::Poll::Pending => return std::task::Poll::Pending,
+        }?;
+        match guard.try_io(|inner| inner.get_ref().write(buf)) {
+            Ok(result) => return std::task::Poll::Ready(result),
+            Err(_would_block) => continue,
+        }
+    }
+}
+
+fn poll_write_vectored(
+    pty: &AsyncPty,
+    cx: &mut std::task::Context<'_>,
+    bufs: &[std::io::IoSlice<'_>],
+) -> std::task::Poll<std::io::Result<usize>> {
+    loop {
+        let mut guard = match pty.poll_write_ready(cx) {
+            std::task::Poll::Ready(guard) => guard,
+            std::task::Poll::Pending => return std::task::Poll::Pending,
+        }?;
+        match guard.try_io(|inner| inner.get_ref().write_vectored(bufs)) {
+            Ok(result) => return std::task::Poll::Ready(result),
+            Err(_would_block) => continue,
+        }
+    }
+}
+
+fn poll_flush(
+    pty: &AsyncPty,
+    cx: &mut std::task::Context<'_>,
+) -> std::task::Poll<std::io::Result<()>> {
+    loop {
+        let mut guard = match pty.poll_write_ready(cx) {
+            std::task::Poll::Ready(guard) => guard,
+            std::task::Poll::Pending => return std::task::Poll::Pending,
+        }?;
+        match guard.try_io(|inner| inner.get_ref().flush()) {
+            Ok(_) => return std::task::Poll::Ready(Ok(())),
+            Err(_would_block) => continue,
+        }
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl futures_io::AsyncRead for Pty {
+    fn poll_read(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &mut [u8],
+    ) -> std::task::Poll<std::io::Result<usize>> {
+        poll_read_slice(self, cx, buf)
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl futures_io::AsyncWrite for Pty {
+    fn poll_write(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &[u8],
+    ) -> std::task::Poll<std::io::Result<usize>> {
+        tokio::io::AsyncWrite::poll_write(self, cx, buf)
+    }
+
+    fn poll_write_vectored(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        bufs: &[std::io::IoSlice<'_>],
+    ) -> std::task::Poll<std::io::Result<usize>> {
+        tokio::io::AsyncWrite::poll_write_vectored(self, cx, bufs)
+    }
+
+    fn poll_flush(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        tokio::io::AsyncWrite::poll_flush(self, cx)
+    }
+
+    fn poll_close(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        tokio::io::AsyncWrite::poll_shutdown(self, cx)
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl<'a> futures_io::AsyncRead for ReadPty<'a> {
+    fn poll_read(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &mut [u8],
+    ) -> std::task::Poll<std::io::Result<usize>> {
+        poll_read_slice(self, cx, buf)
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl<'a> futures_io::AsyncWrite for WritePty<'a> {
+    fn poll_write(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &[u8],
+    ) -> std::task::Poll<std::io::Result<usize>> {
+        tokio::io::AsyncWrite::poll_write(self, cx, buf)
+    }
+
+    fn poll_write_vectored(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        bufs: &[std::io::IoSlice<'_>],
+    ) -> std::task::Poll<std::io::Result<usize>> {
+        tokio::io::AsyncWrite::poll_write_vectored(self, cx, bufs)
+    }
+
+    fn poll_flush(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        tokio::io::AsyncWrite::poll_flush(self, cx)
+    }
+
+    fn poll_close(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        tokio::io::AsyncWrite::poll_shutdown(self, cx)
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl futures_io::AsyncRead for OwnedReadPty {
+    fn poll_read(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &mut [u8],
+    ) -> std::task::Poll<std::io::Result<usize>> {
+        poll_read_slice(self, cx, buf)
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl futures_io::AsyncWrite for OwnedWritePty {
+    fn poll_write(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &[u8],
+    ) -> std::task::Poll<std::io::Result<usize>> {
+        tokio::io::AsyncWrite::poll_write(self, cx, buf)
+    }
+
+    fn poll_write_vectored(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        bufs: &[std::io::IoSlice<'_>],
+    ) -> std::task::Poll<std::io::Result<usize>> {
+        tokio::io::AsyncWrite::poll_write_vectored(self, cx, bufs)
+    }
+
+    fn poll_flush(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        tokio::io::AsyncWrite::poll_flush(self, cx)
+    }
+
+    fn poll_close(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        tokio::io::AsyncWrite::poll_shutdown(self, cx)
+    }
+}
+
+/// Adapts a tokio `poll_read` implementation to the slice-based interface
+/// used by `futures_io::AsyncRead`.
+#[cfg(feature = "futures-io")]
+fn poll_read_slice<R: tokio::io::AsyncRead>(
+    reader: std::pin::Pin<&mut R>,
+    cx: &mut std::task::Context<'_>,
+    buf: &mut [u8],
+) -> std::task::Poll<std::io::Result<usize>> {
+    let mut buf = tokio::io::ReadBuf::new(buf);
+    match reader.poll_read(cx, &mut buf) {
+        std::task::Poll::Ready(Ok(())) => {
+            std::task::Poll::Ready(Ok(buf.filled().len()))
+        }
+        std::task::Poll::Ready(Err(e)) => std::task::Poll::Ready(Err(e)),
+        std::task::Poll::Pending => std::task::Poll::Pending,
+    }
+}
@@ -0,0 +1,183 @@
+/// How long to wait for the host terminal to stop being resized before
+/// propagating its size, by default.
+pub const DEFAULT_DEBOUNCE: std::time::Duration =
+    std::time::Duration::from_millis(50);
+
+/// Identifies a pty registered with a resize forwarder, so that it can be
+/// removed again
+///
+/// Returned by [`ResizeForwarder::add`](crate::ResizeForwarder::add) and
+/// [`blocking::ResizeForwarder::add`](crate::blocking::ResizeForwarder::add).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ResizeTarget(u64);
+
+/// The set of ptys which a resize forwarder keeps in sync with the host
+/// terminal, shared between the forwarder and its background task.
+#[derive(Debug, Default)]
+pub struct Targets {
+    fds: std::sync::Mutex<Vec<(ResizeTarget, std::os::fd::OwnedFd)>>,
+    next: std::sync::atomic::AtomicU64,
+}
+
+impl Targets {
+    pub fn add(
+        &self,
+        fd: std::os::fd::BorrowedFd,
+    ) -> crate::Result<ResizeTarget> {
+        let fd = fd.try_clone_to_owned()?;
+        if let Some(size) = crate::sys::host_term_size() {
+            crate::sys::set_term_size(
+                std::os::fd::AsRawFd::as_raw_fd(&fd),
+                size,
+            )?;
+        }
+        let target = ResizeTarget(
+            self.next.fetch_add(1, std::sync::atomic::Ordering::SeqCst),
+        );
+        self.lock().push((target, fd));
+        Ok(target)
+    }
+
+    #[must_use]
+    pub fn remove(&self, target: ResizeTarget) -> bool {
+        let mut fds = self.lock();
+        let len = fds.len();
+        fds.retain(|(id, _)| *id != target);
+        fds.len() != len
+    }
+
+    pub fn sync(&self) {
+        let Some(size) = crate::sys::host_term_size() else {
+            return;
+        };
+        // ptys which can no longer be resized are dropped, since there is
+        // nobody to report the error to
+        self.lock().retain(|(_, fd)| {
+            crate::sys::set_term_size(
+                std::os::fd::AsRawFd::as_raw_fd(fd),
+                size,
+            )
+            .is_ok()
+        });
+    }
+
+    fn lock(
+        &self,
+    ) -> std::sync::MutexGuard<'_, Vec<(ResizeTarget, std::os::fd::OwnedFd)>>
+    {
+        // the vec is always in a consistent state, so poisoning is harmless
+        self.fds
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Propagates size changes of the terminal attached to stdin to a set of
+/// ptys.
+///
+/// A background task listens for `SIGWINCH`, and when the host terminal is
+/// resized, calls [`resize`](crate::Pty::resize) on each registered pty with
+/// the new size. Bursts of resize events (such as those produced while
+/// dragging a window border) are coalesced, so that the ptys are only
+/// resized once the host terminal has stopped changing size for the
+/// debounce interval.
+///
+/// The forwarder holds its own handle to each registered pty, so they will
+/// not be closed until they are [removed](Self::remove) or the forwarder is
+/// dropped. Dropping the forwarder stops the background task.
+///
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() -> pty_process::Result<()> {
+/// let pty = pty_process::Pty::new()?;
+/// let forwarder = pty_process::ResizeForwarder::new()?;
+/// forwarder.add(&pty)?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// See [`blocking::ResizeForwarder`](crate::blocking::ResizeForwarder) for
+/// an equivalent which doesn't require a tokio runtime.
+#[cfg(feature = "async")]
+pub struct ResizeForwarder {
+    targets: std::sync::Arc<Targets>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+#[cfg(feature = "async")]
+impl ResizeForwarder {
+    /// Starts listening for resizes of the host terminal, with the default
+    /// debounce interval of 50ms. Must be called from within a tokio
+    /// runtime.
+    ///
+    /// # Errors
+    /// Returns an error if the `SIGWINCH` handler could not be installed.
+    pub fn new() -> crate::Result<Self> {
+        Self::with_debounce(DEFAULT_DEBOUNCE)
+    }
+
+    /// Starts listening for resizes of the host terminal, waiting until no
+    /// resizes have happened for `debounce` before propagating the new
+    /// size. Must be called from within a tokio runtime.
+    ///
+    /// # Errors
+    /// Returns an error if the `SIGWINCH` handler could not be installed.
+    pub fn with_debounce(
+        debounce: std::time::Duration,
+    ) -> crate::Result<Self> {
+        let mut signal = tokio::signal::unix::signal(
+            tokio::signal::unix::SignalKind::window_change(),
+        )?;
+        let targets = std::sync::Arc::new(Targets::default());
+        let task = tokio::task::spawn({
+            let targets = std::sync::Arc::clone(&targets);
+            async move {
+                while signal.recv().await.is_some() {
+                    loop {
+                        match tokio::time::timeout(debounce, signal.recv())
+                            .await
+                        {
+                            Ok(Some(())) => {}
+                            Ok(None) => return,
+                            Err(_elapsed) => break,
+                        }
+                    }
+                    targets.sync();
+                }
+            }
+        });
+        Ok(Self { targets, task })
+    }
+
+    /// Registers a pty to be kept in sync with the size of the host
+    /// terminal, and immediately resizes it to the current size of the host
+    /// terminal. Accepts any of the pty types (such as [`Pty`](crate::Pty)
+    /// or [`OwnedWritePty`](crate::OwnedWritePty)). The returned
+    /// [`ResizeTarget`] can be passed to [`remove`](Self::remove) once the
+    /// pty is no longer needed.
+    ///
+    /// # Errors
+    /// Returns an error if the pty's file descriptor could not be
+    /// duplicated, or if the pty could not be resized.
+    pub fn add(
+        &self,
+        pty: &impl std::os::fd::AsFd,
+    ) -> crate::Result<ResizeTarget> {
+        self.targets.add(pty.as_fd())
+    }
+
+    /// Stops keeping the given pty in sync with the size of the host
+    /// terminal, and closes the forwarder's handle to it. Returns `false` if
+    /// the pty was not registered (for instance, if it was already removed).
+    #[must_use]
+    pub fn remove(&self, target: ResizeTarget) -> bool {
+        self.targets.remove(target)
+    }
+}
+
+#[cfg(feature = "async")]
+impl Drop for ResizeForwarder {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
@@ -0,0 +1,179 @@
+//! Tracking of the terminal state of programs running in a pty.
+//!
+//! A [`Terminal`] wraps a [`Pty`](crate::Pty) and feeds all output read
+//! from it through a terminal emulator (provided by the [`vt100`] crate),
+//! which makes it possible to inspect the screen the child process has drawn
+//! rather than matching against the raw bytes it wrote:
+//!
+//! ```no_run
+//! # #[cfg(feature = "async")]
+//! # #[tokio::main]
+//! # async fn main() -> pty_process::Result<()> {
+//! use tokio::io::AsyncReadExt as _;
+//!
+//! let pty = pty_process::Pty::new()?;
+//! pty.resize(pty_process::Size::new(24, 80))?;
+//! let mut cmd = pty_process::Command::new("nethack");
+//! let child = cmd.spawn(&pty.pts()?)?;
+//! let mut term = pty_process::screen::Terminal::new(pty)?;
+//! let mut buf = [0_u8; 4096];
+//! term.read(&mut buf).await?;
+//! println!("{}", term.screen().contents());
+//! println!("{:?}", term.screen().cursor_position());
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "async"))]
+//! # fn main() {}
+//! ```
+//!
+//! See [`blocking::screen::Terminal`](crate::blocking::screen::Terminal) for
+//! the blocking equivalent.
+
+pub use vt100::{Cell, Color, Screen};
+
+/// Rejects sizes which the terminal emulator can't represent.
+pub(crate) fn check_size(size: crate::Size) -> crate::Result<()> {
+    if size.rows() == 0 || size.cols() == 0 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("invalid screen size {size}"),
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Wrapper around a [`Pty`](crate::Pty) which keeps track of the state of
+/// the terminal as output is read from it.
+///
+/// Reads and writes are passed through to the underlying pty unchanged, but
+/// all data read is also processed by a terminal emulator whose state is
+/// available via [`screen`](Self::screen).
+#[cfg(feature = "async")]
+pub struct Terminal {
+    pty: crate::Pty,
+    parser: vt100::Parser,
+}
+
+#[cfg(feature = "async")]
+impl Terminal {
+    /// Creates a new terminal wrapping the given pty. The initial size of the
+    /// screen is taken from the current size of the pty. If the pty has not
+    /// been given a size yet, it is resized to 24x80 first, since no output
+    /// can be processed for an empty screen.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to retrieve or set the terminal
+    /// size.
+    pub fn new(pty: crate::Pty) -> crate::Result<Self> {
+        Self::with_scrollback(pty, 0)
+    }
+
+    /// Creates a new terminal wrapping the given pty, which will retain up
+    /// to `scrollback_len` rows of scrollback. The initial size is chosen
+    /// as for [`new`](Self::new).
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to retrieve or set the terminal
+    /// size.
+    pub fn with_scrollback(
+        pty: crate::Pty,
+        scrollback_len: usize,
+    ) -> crate::Result<Self> {
+        let mut size = pty.size()?;
+        if size.rows() == 0 || size.cols() == 0 {
+            size = crate::Size::new(24, 80);
+            pty.resize(size)?;
+        }
+        Ok(Self {
+            pty,
+            parser: vt100::Parser::new(
+                size.rows(),
+                size.cols(),
+                scrollback_len,
+            ),
+        })
+    }
+
+    /// Returns the current state of the terminal.
+    #[must_use]
+    pub fn screen(&self) -> &Screen {
+        self.parser.screen()
+    }
+
+    /// Change the terminal size associated with the pty, and resize the
+    /// tracked screen to match.
+    ///
+    /// # Errors
+    /// Returns an error if either dimension of `size` is zero (since no
+    /// output can be processed for an empty screen), or if we were unable
+    /// to set the terminal size.
+    pub fn resize(&mut self, size: crate::Size) -> crate::Result<()> {
+        crate::screen::check_size(size)?;
+        self.pty.resize(size)?;
+        self.parser.set_size(size.rows(), size.cols());
+        Ok(())
+    }
+
+    /// Returns a reference to the underlying pty.
+    #[must_use]
+    pub fn get_ref(&self) -> &crate::Pty {
+        &self.pty
+    }
+
+    /// Returns a mutable reference to the underlying pty. Data read from the
+    /// pty directly will not be reflected in the tracked screen.
+    pub fn get_mut(&mut self) -> &mut crate::Pty {
+        &mut self.pty
+    }
+
+    /// Returns the underlying pty.
+    #[must_use]
+    pub fn into_inner(self) -> crate::Pty {
+        self.pty
+    }
+}
+
+#[cfg(feature = "async")]
+impl tokio::io::AsyncRead for Terminal {
+    fn poll_read(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &mut tokio::io::ReadBuf,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        let filled = buf.filled().len();
+        let this = &mut *self;
+        match std::pin::Pin::new(&mut this.pty).poll_read(cx, buf) {
+            std::task::Poll::Ready(Ok(())) => {
+                this.parser.process(&buf.filled()[filled..]);
+                std::task::Poll::Ready(Ok(()))
+            }
+            res => res,
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl tokio::io::AsyncWrite for Terminal {
+    fn poll_write(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &[u8],
+    ) -> std::task::Poll<std::io::Result<usize>> {
+        std::pin::Pin::new(&mut self.pty).poll_write(cx, buf)
+    }
+
+    fn poll_flush(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        std::pin::Pin::new(&mut self.pty).poll_flush(cx)
+    }
+
+    fn poll_shutdown(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), std::io::Error>> {
+        std::pin::Pin::new(&mut self.pty).poll_shutdown(cx)
+    }
+}
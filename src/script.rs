@@ -0,0 +1,514 @@
+//! Recording and playback of sessions in the typescript and timing file
+//! formats used by `script(1)` and `scriptreplay(1)`.
+//!
+//! Two timing file formats are supported: the classic format written by
+//! `script -t`, which only records output, and the advanced format written
+//! by `script --log-io --log-timing`, which additionally records input and
+//! window size changes.
+//!
+//! A [`Recording`] wraps the read half of a pty (anything implementing
+//! [`AsyncRead`](tokio::io::AsyncRead), such as a [`Pty`](crate::Pty),
+//! [`ReadPty`](crate::ReadPty), or [`OwnedReadPty`](crate::OwnedReadPty))
+//! and writes everything read from it to a typescript and timing file pair.
+//! A [`Reader`] parses such a pair back into timed chunks.
+//!
+//! See [`blocking::script`](crate::blocking::script) for the blocking
+//! equivalent.
+
+/// The timing file format to use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// The classic format (`script -t`), in which each line of the timing
+    /// file contains a delay and a number of bytes of output.
+    Classic,
+    /// The advanced format (`script --log-io --log-timing`), in which each
+    /// line of the timing file is tagged with the type of the event, and
+    /// input and window size changes are recorded in addition to output.
+    Advanced,
+}
+
+/// An event recorded in a typescript.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// Data written by the child process to the pty.
+    Output(Vec<u8>),
+    /// Data written to the pty to be read by the child process.
+    Input(Vec<u8>),
+    /// The terminal was resized.
+    Resize(crate::Size),
+}
+
+/// Writes events to a typescript and timing file pair.
+pub struct Writer<L: std::io::Write, T: std::io::Write> {
+    log: L,
+    timing: T,
+    format: Format,
+    last: std::time::Instant,
+}
+
+impl<L: std::io::Write, T: std::io::Write> Writer<L, T> {
+    /// Creates a new writer, and writes the typescript header (and, for the
+    /// advanced format, the timing file header) describing a terminal of the
+    /// given size.
+    ///
+    /// # Errors
+    /// Returns an error if writing the headers failed.
+    pub fn new(
+        mut log: L,
+        mut timing: T,
+        format: Format,
+        size: crate::Size,
+    ) -> std::io::Result<Self> {
+        let start = format_time(std::time::SystemTime::now());
+        writeln!(
+            log,
+            "Script started on {start} [COLUMNS=\"{}\" LINES=\"{}\"]",
+            size.cols(),
+            size.rows(),
+        )?;
+        log.flush()?;
+        if format == Format::Advanced {
+            writeln!(timing, "H 0.000000 START_TIME {start}")?;
+            writeln!(timing, "H 0.000000 COLUMNS {}", size.cols())?;
+            writeln!(timing, "H 0.000000 LINES {}", size.rows())?;
+            timing.flush()?;
+        }
+        Ok(Self {
+            log,
+            timing,
+            format,
+            last: std::time::Instant::now(),
+        })
+    }
+
+    /// Records data written by the child process.
+    ///
+    /// # Errors
+    /// Returns an error if writing to either file failed.
+    pub fn record_output(&mut self, data: &[u8]) -> std::io::Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+        let delay = self.delay();
+        match self.format {
+            Format::Classic => {
+                writeln!(self.timing, "{delay:.6} {}", data.len())?;
+            }
+            Format::Advanced => {
+                writeln!(self.timing, "O {delay:.6} {}", data.len())?;
+            }
+        }
+        self.write_data(data)
+    }
+
+    /// Records data sent to the child process. Input is only recorded in
+    /// the advanced format, and is ignored otherwise.
+    ///
+    /// # Errors
+    /// Returns an error if writing to either file failed.
+    pub fn record_input(&mut self, data: &[u8]) -> std::io::Result<()> {
+        if data.is_empty() || self.format != Format::Advanced {
+            return Ok(());
+        }
+        let delay = self.delay();
+        writeln!(self.timing, "I {delay:.6} {}", data.len())?;
+        self.write_data(data)
+    }
+
+    /// Records a change in the terminal size. Size changes are only recorded
+    /// in the advanced format, and are ignored otherwise.
+    ///
+    /// # Errors
+    /// Returns an error if writing to the timing file failed.
+    pub fn record_resize(
+        &mut self,
+        size: crate::Size,
+    ) -> std::io::Result<()> {
+        if self.format != Format::Advanced {
+            return Ok(());
+        }
+        let delay = self.delay();
+        writeln!(
+            self.timing,
+            "S {delay:.6} SIGWINCH ROWS={} COLS={}",
+            size.rows(),
+            size.cols()
+        )?;
+        self.timing.flush()
+    }
+
+    /// Writes the typescript trailer, including the exit code of the child
+    /// process if known, and returns the underlying writers.
+    ///
+    /// # Errors
+    /// Returns an error if writing the trailer failed.
+    pub fn finish(
+        mut self,
+        exit_code: Option<i32>,
+    ) -> std::io::Result<(L, T)> {
+        let done = format_time(std::time::SystemTime::now());
+        write!(self.log, "\nScript done on {done}")?;
+        if let Some(code) = exit_code {
+            write!(self.log, " [COMMAND_EXIT_CODE=\"{code}\"]")?;
+        }
+        writeln!(self.log)?;
+        self.log.flush()?;
+        self.timing.flush()?;
+        Ok((self.log, self.timing))
+    }
+
+    fn delay(&mut self) -> f64 {
+        let now = std::time::Instant::now();
+        let delay = now - self.last;
+        self.last = now;
+        delay.as_secs_f64()
+    }
+
+    fn write_data(&mut self, data: &[u8]) -> std::io::Result<()> {
+        self.log.write_all(data)?;
+        self.log.flush()?;
+        self.timing.flush()
+    }
+}
+
+/// Formats the given time as `YYYY-MM-DD HH:MM:SS+00:00` in UTC.
+fn format_time(time: std::time::SystemTime) -> String {
+    let secs = time
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let (days, secs) = (secs / 86400, secs % 86400);
+    let (hour, min, sec) = (secs / 3600, secs % 3600 / 60, secs % 60);
+
+    // civil_from_days, from
+    // http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+
+    format!("{year:04}-{month:02}-{day:02} {hour:02}:{min:02}:{sec:02}+00:00")
+}
+
+/// Reads timed events back from a typescript and timing file pair.
+///
+/// The format of the timing file is detected automatically. For the
+/// advanced format, input and output are expected to have been logged to
+/// the same typescript (as with `script --log-io`).
+pub struct Reader<L: std::io::BufRead, T: std::io::BufRead> {
+    log: L,
+    timing: T,
+    started: bool,
+}
+
+impl<L: std::io::BufRead, T: std::io::BufRead> Reader<L, T> {
+    /// Creates a new reader for the given typescript and timing file.
+    pub fn new(log: L, timing: T) -> Self {
+        Self {
+            log,
+            timing,
+            started: false,
+        }
+    }
+
+    /// Reads the next event, along with the delay since the previous event.
+    /// Returns `None` at the end of the timing file.
+    ///
+    /// # Errors
+    /// Returns an error if reading from either file failed, or if the timing
+    /// file could not be parsed.
+    pub fn next_event(
+        &mut self,
+    ) -> std::io::Result<Option<(std::time::Duration, Event)>> {
+        if !self.started {
+            self.skip_header()?;
+            self.started = true;
+        }
+
+        let mut extra_delay = std::time::Duration::ZERO;
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if self.timing.read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+            let mut fields = line.split_whitespace();
+            let Some(first) = fields.next() else {
+                continue;
+            };
+            let (tag, delay) =
+                if first.starts_with(|c: char| c.is_ascii_digit()) {
+                    ("O", first)
+                } else {
+                    (first, fields.next().ok_or_else(|| invalid_data(&line))?)
+                };
+            let delay = delay
+                .parse()
+                .ok()
+                .and_then(|d| std::time::Duration::try_from_secs_f64(d).ok())
+                .ok_or_else(|| invalid_data(&line))?
+                + extra_delay;
+            let event = match tag {
+                "O" | "I" => {
+                    let len: usize = fields
+                        .next()
+                        .and_then(|len| len.parse().ok())
+                        .ok_or_else(|| invalid_data(&line))?;
+                    let mut data = vec![0; len];
+                    self.log.read_exact(&mut data)?;
+                    if tag == "O" {
+                        Event::Output(data)
+                    } else {
+                        Event::Input(data)
+                    }
+                }
+                "S" => {
+                    let mut rows = None;
+                    let mut cols = None;
+                    for field in fields {
+                        if let Some(n) = field.strip_prefix("ROWS=") {
+                            rows = n.parse().ok();
+                        } else if let Some(n) = field.strip_prefix("COLS=") {
+                            cols = n.parse().ok();
+                        }
+                    }
+                    if let (Some(rows), Some(cols)) = (rows, cols) {
+                        Event::Resize(crate::Size::new(rows, cols))
+                    } else {
+                        // other signals aren't interesting for replay
+                        extra_delay = delay;
+                        continue;
+                    }
+                }
+                "H" => {
+                    extra_delay = delay;
+                    continue;
+                }
+                _ => return Err(invalid_data(&line)),
+            };
+            return Ok(Some((delay, event)));
+        }
+    }
+
+    /// Replays the output events into `writer`, sleeping for the recorded
+    /// delay (divided by `divisor`) before each one, as `scriptreplay`
+    /// does.
+    ///
+    /// # Errors
+    /// Returns an error if `divisor` is not positive and finite, or if
+    /// reading the recording or writing to `writer` failed.
+    pub fn replay<W: std::io::Write>(
+        &mut self,
+        mut writer: W,
+        divisor: f64,
+    ) -> std::io::Result<()> {
+        if !(divisor > 0.0 && divisor.is_finite()) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "replay divisor must be positive and finite",
+            ));
+        }
+        while let Some((delay, event)) = self.next_event()? {
+            let delay = std::time::Duration::try_from_secs_f64(
+                delay.as_secs_f64() / divisor,
+            )
+            .map_err(|_| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "scaled delay is too large",
+                )
+            })?;
+            std::thread::sleep(delay);
+            if let Event::Output(data) = event {
+                writer.write_all(&data)?;
+                writer.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn skip_header(&mut self) -> std::io::Result<()> {
+        const HEADER: &[u8] = b"Script started on ";
+
+        let buf = self.log.fill_buf()?;
+        if buf.starts_with(HEADER)
+            || (buf.len() < HEADER.len() && HEADER.starts_with(buf))
+        {
+            let mut line = vec![];
+            self.log.read_until(b'\n', &mut line)?;
+        }
+        Ok(())
+    }
+}
+
+impl<L: std::io::BufRead, T: std::io::BufRead> Iterator for Reader<L, T> {
+    type Item = std::io::Result<(std::time::Duration, Event)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_event().transpose()
+    }
+}
+
+fn invalid_data(line: &str) -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        format!("invalid timing file entry: {:?}", line.trim_end()),
+    )
+}
+
+/// Wrapper around the read half of a pty which records everything read
+/// from it to a typescript and timing file pair.
+///
+/// Events are buffered in memory and written to the typescript and timing
+/// file as the recording is read from, without blocking the executor.
+/// [`finish`](Self::finish) writes out anything that remains.
+#[cfg(feature = "async")]
+pub struct Recording<R, L, T> {
+    pty: R,
+    log: L,
+    timing: T,
+    writer: Writer<Vec<u8>, Vec<u8>>,
+}
+
+#[cfg(feature = "async")]
+impl<R, L, T> Recording<R, L, T>
+where
+    R: tokio::io::AsyncRead + Unpin,
+    L: tokio::io::AsyncWrite + Unpin,
+    T: tokio::io::AsyncWrite + Unpin,
+{
+    /// Starts recording data read from `pty` to the given typescript and
+    /// timing file, in the given format. `size` is recorded in the header of
+    /// the typescript.
+    ///
+    /// # Errors
+    /// Returns an error if formatting the headers failed.
+    pub fn new(
+        pty: R,
+        log: L,
+        timing: T,
+        format: Format,
+        size: crate::Size,
+    ) -> crate::Result<Self> {
+        Ok(Self {
+            pty,
+            log,
+            timing,
+            writer: Writer::new(vec![], vec![], format, size)?,
+        })
+    }
+
+    /// Returns a mutable reference to the underlying writer, for instance
+    /// to record input or resize events. Events recorded through it are
+    /// buffered along with the rest of the recording.
+    pub fn writer(&mut self) -> &mut Writer<Vec<u8>, Vec<u8>> {
+        &mut self.writer
+    }
+
+    /// Returns a reference to the wrapped pty.
+    pub fn get_ref(&self) -> &R {
+        &self.pty
+    }
+
+    /// Stops recording, writing any buffered events and the typescript
+    /// trailer with the given exit code, and returns the wrapped pty along
+    /// with the typescript and timing file writers.
+    ///
+    /// # Errors
+    /// Returns an error if writing the buffered events or the trailer
+    /// failed.
+    pub async fn finish(
+        self,
+        exit_code: Option<i32>,
+    ) -> crate::Result<(R, L, T)> {
+        use tokio::io::AsyncWriteExt as _;
+
+        let Self {
+            pty,
+            mut log,
+            mut timing,
+            writer,
+        } = self;
+        let (log_buf, timing_buf) = writer.finish(exit_code)?;
+        log.write_all(&log_buf).await?;
+        log.flush().await?;
+        timing.write_all(&timing_buf).await?;
+        timing.flush().await?;
+        Ok((pty, log, timing))
+    }
+
+    /// Writes as much of the buffered typescript and timing data to the
+    /// underlying writers as possible, returning `Ready` once there is none
+    /// left.
+    fn poll_write_events(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        let log = poll_write_buf(&mut self.log, &mut self.writer.log, cx)?;
+        let timing =
+            poll_write_buf(&mut self.timing, &mut self.writer.timing, cx)?;
+        if log.is_ready() && timing.is_ready() {
+            std::task::Poll::Ready(Ok(()))
+        } else {
+            std::task::Poll::Pending
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+fn poll_write_buf<W: tokio::io::AsyncWrite + Unpin>(
+    writer: &mut W,
+    buf: &mut Vec<u8>,
+    cx: &mut std::task::Context<'_>,
+) -> std::task::Poll<std::io::Result<()>> {
+    while !buf.is_empty() {
+        match std::pin::Pin::new(&mut *writer).poll_write(cx, buf) {
+            std::task::Poll::Ready(Ok(0)) => {
+                return std::task::Poll::Ready(Err(
+                    std::io::ErrorKind::WriteZero.into(),
+                ))
+            }
+            std::task::Poll::Ready(Ok(bytes)) => {
+                buf.drain(..bytes);
+            }
+            std::task::Poll::Ready(Err(e)) => {
+                return std::task::Poll::Ready(Err(e))
+            }
+            std::task::Poll::Pending => return std::task::Poll::Pending,
+        }
+    }
+    std::task::Poll::Ready(Ok(()))
+}
+
+#[cfg(feature = "async")]
+impl<R, L, T> tokio::io::AsyncRead for Recording<R, L, T>
+where
+    R: tokio::io::AsyncRead + Unpin,
+    L: tokio::io::AsyncWrite + Unpin,
+    T: tokio::io::AsyncWrite + Unpin,
+{
+    fn poll_read(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &mut tokio::io::ReadBuf,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        let filled = buf.filled().len();
+        let this = &mut *self;
+        match std::pin::Pin::new(&mut this.pty).poll_read(cx, buf) {
+            std::task::Poll::Ready(Ok(())) => {
+                this.writer.record_output(&buf.filled()[filled..])?;
+            }
+            res => return res,
+        }
+        // the data has already been read, so if the writers aren't ready
+        // yet the events will be written on a later call
+        match this.poll_write_events(cx) {
+            std::task::Poll::Ready(Err(e)) => std::task::Poll::Ready(Err(e)),
+            _ => std::task::Poll::Ready(Ok(())),
+        }
+    }
+}
@@ -3,31 +3,148 @@ use std::os::{
     unix::prelude::OsStrExt as _,
 };
 
+/// The value of a control character which has been disabled
+#[cfg(any(target_os = "linux", target_os = "android"))]
+const VDISABLE: u8 = 0;
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+const VDISABLE: u8 = 0xff;
+
+// the second field is the devpts directory containing the child end of the
+// pty, if it wasn't allocated from the default /dev/ptmx
 #[derive(Debug)]
-pub struct Pty(std::os::fd::OwnedFd);
+pub struct Pty(std::os::fd::OwnedFd, Option<std::path::PathBuf>);
 
 impl Pty {
     pub fn open() -> crate::Result<Self> {
+        // setting CLOEXEC when opening the fd prevents it from leaking
+        // into a process spawned by another thread before we get the chance
+        // to set it separately, but not every platform supports it
+        #[cfg(any(
+            target_os = "linux",
+            target_os = "android",
+            target_os = "freebsd",
+            target_os = "netbsd"
+        ))]
+        let pt = rustix::pty::openpt(
+            rustix::pty::OpenptFlags::RDWR
+                | rustix::pty::OpenptFlags::NOCTTY
+                | rustix::pty::OpenptFlags::CLOEXEC,
+        )?;
+        #[cfg(not(any(
+            target_os = "linux",
+            target_os = "android",
+            target_os = "freebsd",
+            target_os = "netbsd"
+        )))]
         let pt = rustix::pty::openpt(
-            // can't use CLOEXEC here because it's linux-specific
             rustix::pty::OpenptFlags::RDWR | rustix::pty::OpenptFlags::NOCTTY,
         )?;
         rustix::pty::grantpt(&pt)?;
         rustix::pty::unlockpt(&pt)?;
 
-        let mut flags = rustix::io::fcntl_getfd(&pt)?;
-        flags |= rustix::io::FdFlags::CLOEXEC;
-        rustix::io::fcntl_setfd(&pt, flags)?;
+        #[cfg(not(any(
+            target_os = "linux",
+            target_os = "android",
+            target_os = "freebsd",
+            target_os = "netbsd"
+        )))]
+        {
+            let mut flags = rustix::io::fcntl_getfd(&pt)?;
+            flags |= rustix::io::FdFlags::CLOEXEC;
+            rustix::io::fcntl_setfd(&pt, flags)?;
+        }
+
+        Ok(Self(pt, None))
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn open_at(ptmx: &std::path::Path) -> crate::Result<Self> {
+        let pt = rustix::fs::open(
+            ptmx,
+            rustix::fs::OFlags::RDWR
+                | rustix::fs::OFlags::NOCTTY
+                | rustix::fs::OFlags::CLOEXEC,
+            rustix::fs::Mode::empty(),
+        )?;
+        rustix::pty::grantpt(&pt)?;
+        rustix::pty::unlockpt(&pt)?;
+
+        Ok(Self(pt, devpts_dir(ptmx)))
+    }
 
-        Ok(Self(pt))
+    pub fn from_fd(fd: std::os::fd::OwnedFd) -> Self {
+        Self(fd, None)
     }
 
     pub fn set_term_size(&self, size: crate::Size) -> crate::Result<()> {
-        let size = libc::winsize::from(size);
-        let fd = self.0.as_raw_fd();
+        set_term_size(self.0.as_raw_fd(), size)
+    }
+
+    pub fn term_size(&self) -> crate::Result<crate::Size> {
+        get_term_size(self.0.as_raw_fd())
+    }
+
+    pub fn termios(&self) -> crate::Result<crate::Termios> {
+        Ok(crate::Termios(rustix::termios::tcgetattr(&self.0)?))
+    }
+
+    /// Returns the byte to write to the pty to have its line discipline
+    /// act on the given control character, as currently configured.
+    pub fn control_char(&self, cc: crate::ControlChar) -> crate::Result<u8> {
+        let byte = self.termios()?.control_char(cc);
+        if byte == VDISABLE {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("the {cc:?} control character is disabled"),
+            )
+            .into());
+        }
+        Ok(byte)
+    }
+
+    pub fn foreground_process_group(&self) -> crate::Result<u32> {
+        Ok(pid_to_u32(rustix::termios::tcgetpgrp(&self.0)?))
+    }
+
+    pub fn session_id(&self) -> crate::Result<u32> {
+        Ok(pid_to_u32(rustix::termios::tcgetsid(&self.0)?))
+    }
+
+    pub fn signal_foreground(
+        &self,
+        signal: rustix::process::Signal,
+    ) -> crate::Result<()> {
+        // TIOCSIG doesn't require us to have permission to signal the
+        // processes in question, but linux only allows it for the signals
+        // that can be generated by the terminal itself (SIGINT, SIGQUIT,
+        // and SIGTSTP)
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        {
+            #[allow(clippy::as_conversions)]
+            let sig = signal as libc::c_int;
+            // TODO: upstream this to rustix
+            let ret = unsafe {
+                libc::ioctl(self.0.as_raw_fd(), libc::TIOCSIG, sig)
+            };
+            if ret != -1 {
+                return Ok(());
+            }
+        }
+
+        let pgrp = rustix::termios::tcgetpgrp(&self.0)?;
+        rustix::process::kill_process_group(pgrp, signal)?;
+        Ok(())
+    }
+
+    pub fn set_packet_mode(&self, enabled: bool) -> crate::Result<()> {
+        let enabled = libc::c_int::from(enabled);
         // TODO: upstream this to rustix
         let ret = unsafe {
-            libc::ioctl(fd, libc::TIOCSWINSZ, std::ptr::addr_of!(size))
+            libc::ioctl(
+                self.0.as_raw_fd(),
+                libc::TIOCPKT,
+                std::ptr::addr_of!(enabled),
+            )
         };
         if ret == -1 {
             Err(rustix::io::Errno::from_raw_os_error(
@@ -39,17 +156,118 @@ impl Pty {
         }
     }
 
+    pub fn set_termios(
+        &self,
+        when: crate::SetArg,
+        termios: &crate::Termios,
+    ) -> crate::Result<()> {
+        Ok(rustix::termios::tcsetattr(
+            &self.0,
+            when.into(),
+            &termios.0,
+        )?)
+    }
+
     pub fn pts(&self) -> crate::Result<Pts> {
-        Ok(Pts(std::fs::OpenOptions::new()
+        // ptsname always returns a path under /dev/pts, which is wrong if
+        // the pty was allocated from some other devpts instance
+        let path = if let Some(dir) = &self.1 {
+            dir.join(self.pts_index()?.to_string())
+        } else {
+            std::path::PathBuf::from(std::ffi::OsStr::from_bytes(
+                rustix::pty::ptsname(&self.0, vec![])?.as_bytes(),
+            ))
+        };
+
+        // opening the peer directly from the master avoids looking it up
+        // by path, which can resolve to the wrong device (or nothing at
+        // all) if our /dev/pts is not the devpts instance that the master
+        // belongs to, as is common in containers. TIOCGPTPEER is only
+        // available since linux 4.13, so fall back to the path otherwise.
+        #[cfg(target_os = "linux")]
+        match rustix::pty::ioctl_tiocgptpeer(
+            &self.0,
+            rustix::pty::OpenptFlags::RDWR
+                | rustix::pty::OpenptFlags::NOCTTY
+                | rustix::pty::OpenptFlags::CLOEXEC,
+        ) {
+            Ok(fd) => return Ok(Pts(fd, path)),
+            Err(rustix::io::Errno::INVAL | rustix::io::Errno::NOTTY) => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        let fd = std::fs::OpenOptions::new()
             .read(true)
             .write(true)
-            .open(std::ffi::OsStr::from_bytes(
-                rustix::pty::ptsname(&self.0, vec![])?.as_bytes(),
-            ))?
-            .into()))
+            .open(&path)?
+            .into();
+        Ok(Pts(fd, path))
+    }
+
+    /// Reads into possibly uninitialized memory, returning the number of
+    /// bytes at the start of `buf` which are now initialized. This is
+    /// otherwise the same as [`std::io::Read::read`].
+    pub fn read_uninit(
+        &self,
+        buf: &mut [std::mem::MaybeUninit<u8>],
+    ) -> std::io::Result<usize> {
+        read_uninit(&self.0, buf)
+    }
+
+    /// Returns whether every file descriptor for the child end of the pty
+    /// has been closed.
+    pub fn is_hung_up(&self) -> bool {
+        is_hung_up(&self.0)
+    }
+
+    pub fn pts_index(&self) -> crate::Result<u32> {
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        {
+            let mut index: libc::c_uint = 0;
+            // TODO: upstream this to rustix
+            let ret = unsafe {
+                libc::ioctl(
+                    self.0.as_raw_fd(),
+                    libc::TIOCGPTN,
+                    std::ptr::addr_of_mut!(index),
+                )
+            };
+            if ret == -1 {
+                Err(rustix::io::Errno::from_raw_os_error(
+                    std::io::Error::last_os_error()
+                        .raw_os_error()
+                        .unwrap_or(0),
+                )
+                .into())
+            } else {
+                Ok(index)
+            }
+        }
+
+        // elsewhere, the index is the number at the end of the device
+        // name (/dev/pts/N on the BSDs, /dev/ttysNNN on macOS)
+        #[cfg(not(any(target_os = "linux", target_os = "android")))]
+        {
+            let name = rustix::pty::ptsname(&self.0, vec![])?;
+            let name = name.to_bytes();
+            let digits = name
+                .iter()
+                .rev()
+                .take_while(|byte| byte.is_ascii_digit())
+                .count();
+            std::str::from_utf8(&name[name.len() - digits..])
+                .ok()
+                .and_then(|index| index.parse().ok())
+                .ok_or_else(|| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        "pty device name does not end with an index",
+                    )
+                    .into()
+                })
+        }
     }
 
-    #[cfg(feature = "async")]
     pub fn set_nonblocking(&self) -> rustix::io::Result<()> {
         let mut opts = rustix::fs::fcntl_getfl(&self.0)?;
         opts |= rustix::fs::OFlags::NONBLOCK;
@@ -57,11 +275,19 @@ impl Pty {
 
         Ok(())
     }
+
+    pub fn set_blocking(&self) -> rustix::io::Result<()> {
+        let mut opts = rustix::fs::fcntl_getfl(&self.0)?;
+        opts -= rustix::fs::OFlags::NONBLOCK;
+        rustix::fs::fcntl_setfl(&self.0, opts)?;
+
+        Ok(())
+    }
 }
 
 impl From<Pty> for std::os::fd::OwnedFd {
     fn from(pty: Pty) -> Self {
-        let Pty(nix_ptymaster) = pty;
+        let Pty(nix_ptymaster, _) = pty;
         let raw_fd = nix_ptymaster.as_raw_fd();
         std::mem::forget(nix_ptymaster);
 
@@ -90,7 +316,7 @@ impl std::os::fd::AsRawFd for Pty {
 
 impl std::io::Read for Pty {
     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
-        rustix::io::read(&self.0, buf).map_err(std::io::Error::from)
+        read(&self.0, buf)
     }
 }
 
@@ -99,6 +325,13 @@ impl std::io::Write for Pty {
         rustix::io::write(&self.0, buf).map_err(std::io::Error::from)
     }
 
+    fn write_vectored(
+        &mut self,
+        bufs: &[std::io::IoSlice<'_>],
+    ) -> std::io::Result<usize> {
+        write_vectored(self, bufs)
+    }
+
     fn flush(&mut self) -> std::io::Result<()> {
         Ok(())
     }
@@ -106,7 +339,7 @@ impl std::io::Write for Pty {
 
 impl std::io::Read for &Pty {
     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
-        rustix::io::read(&self.0, buf).map_err(std::io::Error::from)
+        read(&self.0, buf)
     }
 }
 
@@ -115,14 +348,25 @@ impl std::io::Write for &Pty {
         rustix::io::write(&self.0, buf).map_err(std::io::Error::from)
     }
 
+    fn write_vectored(
+        &mut self,
+        bufs: &[std::io::IoSlice<'_>],
+    ) -> std::io::Result<usize> {
+        write_vectored(self, bufs)
+    }
+
     fn flush(&mut self) -> std::io::Result<()> {
         Ok(())
     }
 }
 
-pub struct Pts(std::os::fd::OwnedFd);
+pub struct Pts(std::os::fd::OwnedFd, std::path::PathBuf);
 
 impl Pts {
+    pub fn path(&self) -> &std::path::Path {
+        &self.1
+    }
+
     pub fn setup_subprocess(
         &self,
     ) -> std::io::Result<(
@@ -137,18 +381,27 @@ impl Pts {
         ))
     }
 
+    pub fn term_size(&self) -> crate::Result<crate::Size> {
+        get_term_size(self.0.as_raw_fd())
+    }
+
     pub fn session_leader(&self) -> impl FnMut() -> std::io::Result<()> {
         let pts_fd = self.0.as_raw_fd();
-        move || {
-            rustix::process::setsid()?;
-            rustix::process::ioctl_tiocsctty(unsafe {
-                std::os::fd::BorrowedFd::borrow_raw(pts_fd)
-            })?;
-            Ok(())
-        }
+        move || session_leader(pts_fd)
     }
 }
 
+/// Makes the current process the leader of a new session, with the given
+/// pty as its controlling terminal. Only intended to be called in a child
+/// process between `fork` and `exec`, while `pts_fd` is still open.
+pub fn session_leader(pts_fd: std::os::fd::RawFd) -> std::io::Result<()> {
+    rustix::process::setsid()?;
+    rustix::process::ioctl_tiocsctty(unsafe {
+        std::os::fd::BorrowedFd::borrow_raw(pts_fd)
+    })?;
+    Ok(())
+}
+
 impl From<Pts> for std::os::fd::OwnedFd {
     fn from(pts: Pts) -> Self {
         pts.0
@@ -166,3 +419,151 @@ impl std::os::fd::AsRawFd for Pts {
         self.0.as_raw_fd()
     }
 }
+
+fn get_term_size(fd: std::os::fd::RawFd) -> crate::Result<crate::Size> {
+    let mut size = libc::winsize {
+        ws_row: 0,
+        ws_col: 0,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    };
+    // TODO: upstream this to rustix
+    let ret = unsafe {
+        libc::ioctl(fd, libc::TIOCGWINSZ, std::ptr::addr_of_mut!(size))
+    };
+    if ret == -1 {
+        Err(rustix::io::Errno::from_raw_os_error(
+            std::io::Error::last_os_error().raw_os_error().unwrap_or(0),
+        )
+        .into())
+    } else {
+        Ok(size.into())
+    }
+}
+
+fn pid_to_u32(pid: rustix::process::Pid) -> u32 {
+    // pids are always positive
+    pid.as_raw_nonzero().get().unsigned_abs()
+}
+
+pub fn set_term_size(
+    fd: std::os::fd::RawFd,
+    size: crate::Size,
+) -> crate::Result<()> {
+    let size = libc::winsize::from(size);
+    // TODO: upstream this to rustix
+    let ret = unsafe {
+        libc::ioctl(fd, libc::TIOCSWINSZ, std::ptr::addr_of!(size))
+    };
+    if ret == -1 {
+        Err(rustix::io::Errno::from_raw_os_error(
+            std::io::Error::last_os_error().raw_os_error().unwrap_or(0),
+        )
+        .into())
+    } else {
+        Ok(())
+    }
+}
+
+/// Returns the devpts directory which a pty opened from the given ptmx node
+/// belongs to. This is either the directory containing the node (for the
+/// ptmx node inside a devpts instance), or the `pts` directory next to it
+/// (for a standalone ptmx node, such as `/dev/ptmx`), which is the same
+/// lookup that the kernel does when the node is opened.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn devpts_dir(ptmx: &std::path::Path) -> Option<std::path::PathBuf> {
+    let is_devpts = |dir: &std::path::Path| {
+        rustix::fs::statfs(dir)
+            .is_ok_and(|fs| fs.f_type == libc::DEVPTS_SUPER_MAGIC)
+    };
+    let parent = ptmx.parent()?;
+    if is_devpts(parent) {
+        return Some(parent.to_path_buf());
+    }
+    let pts = parent.join("pts");
+    is_devpts(&pts).then_some(pts)
+}
+
+/// Returns the size of the terminal attached to stdin, if any.
+pub fn host_term_size() -> Option<crate::Size> {
+    get_term_size(rustix::stdio::stdin().as_raw_fd()).ok()
+}
+
+fn read(fd: &std::os::fd::OwnedFd, buf: &mut [u8]) -> std::io::Result<usize> {
+    eof_on_hangup(fd, rustix::io::read(fd, buf))
+}
+
+fn read_uninit(
+    fd: &std::os::fd::OwnedFd,
+    buf: &mut [std::mem::MaybeUninit<u8>],
+) -> std::io::Result<usize> {
+    eof_on_hangup(
+        fd,
+        rustix::io::read_uninit(fd, buf).map(|(filled, _)| filled.len()),
+    )
+}
+
+fn eof_on_hangup(
+    fd: &std::os::fd::OwnedFd,
+    res: rustix::io::Result<usize>,
+) -> std::io::Result<usize> {
+    match res {
+        // linux returns EIO when reading from a pty whose other end has
+        // been closed, but that is just the normal way for the session to
+        // end, so report it as end of file instead. other platforms (and
+        // genuine I/O errors) don't report a hangup alongside the error.
+        Err(rustix::io::Errno::IO) if is_hung_up(fd) => Ok(0),
+        res => res.map_err(std::io::Error::from),
+    }
+}
+
+fn write_vectored(
+    pty: &Pty,
+    bufs: &[std::io::IoSlice<'_>],
+) -> std::io::Result<usize> {
+    rustix::io::writev(&pty.0, bufs).map_err(std::io::Error::from)
+}
+
+fn is_hung_up(fd: &std::os::fd::OwnedFd) -> bool {
+    let mut fds = [rustix::event::PollFd::new(
+        fd,
+        rustix::event::PollFlags::empty(),
+    )];
+    matches!(rustix::event::poll(&mut fds, 0), Ok(1))
+        && fds[0].revents().contains(rustix::event::PollFlags::HUP)
+}
+
+/// Puts the terminal attached to stdin into raw mode, restoring its previous
+/// settings when dropped (including during a panic). Does nothing if stdin
+/// is not a terminal.
+pub struct RawGuard(Option<rustix::termios::Termios>);
+
+impl RawGuard {
+    pub fn new() -> crate::Result<Self> {
+        let stdin = rustix::stdio::stdin();
+        if !rustix::termios::isatty(stdin) {
+            return Ok(Self(None));
+        }
+        let termios = rustix::termios::tcgetattr(stdin)?;
+        let mut raw = termios.clone();
+        raw.make_raw();
+        rustix::termios::tcsetattr(
+            stdin,
+            rustix::termios::OptionalActions::Now,
+            &raw,
+        )?;
+        Ok(Self(Some(termios)))
+    }
+}
+
+impl Drop for RawGuard {
+    fn drop(&mut self) {
+        if let Some(termios) = &self.0 {
+            let _ = rustix::termios::tcsetattr(
+                rustix::stdio::stdin(),
+                rustix::termios::OptionalActions::Now,
+                termios,
+            );
+        }
+    }
+}
@@ -0,0 +1,253 @@
+/// Identifies the format of the header sent along with a pty, so that a
+/// receiver built from a different version of this crate fails cleanly.
+const HEADER_VERSION: u8 = 1;
+
+/// The version byte, the four size fields, the child pid and pts index,
+/// the four termios flag fields, and the control characters
+const HEADER_LEN: usize = 1 + 4 * 2 + 4 + 4 + 4 * 8 + 16;
+
+/// Information about a pty which is sent along with it to another process
+///
+/// Obtain an instance via [`Pty::metadata`](crate::Pty::metadata) (or
+/// [`blocking::Pty::metadata`](crate::blocking::Pty::metadata)), and pass
+/// it to [`Pty::send_to`](crate::Pty::send_to). The receiving process gets
+/// it back from [`Pty::recv_from`](crate::Pty::recv_from).
+#[derive(Debug, Clone)]
+pub struct PtyMetadata {
+    size: crate::Size,
+    termios: crate::Termios,
+    child_pid: Option<u32>,
+    pts_index: u32,
+}
+
+impl PtyMetadata {
+    pub(crate) fn new(pty: &crate::sys::Pty) -> crate::Result<Self> {
+        Ok(Self {
+            size: pty.term_size()?,
+            termios: pty.termios()?,
+            child_pid: None,
+            pts_index: pty.pts_index()?,
+        })
+    }
+
+    /// Returns the terminal size of the pty at the time it was sent.
+    #[must_use]
+    pub fn size(&self) -> crate::Size {
+        self.size
+    }
+
+    /// Returns the terminal attributes of the pty at the time it was sent.
+    #[must_use]
+    pub fn termios(&self) -> &crate::Termios {
+        &self.termios
+    }
+
+    /// Returns the process id of the child process running in the pty, if
+    /// one was set by the sender.
+    #[must_use]
+    pub fn child_pid(&self) -> Option<u32> {
+        self.child_pid
+    }
+
+    /// Sets the process id of the child process running in the pty, so
+    /// that the receiving process can keep track of it.
+    pub fn set_child_pid(&mut self, pid: Option<u32>) {
+        self.child_pid = pid;
+    }
+
+    /// Returns the index of the pty (see
+    /// [`Pty::pts_index`](crate::Pty::pts_index)).
+    #[must_use]
+    pub fn pts_index(&self) -> u32 {
+        self.pts_index
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let termios = &self.termios.0;
+        let mut buf = Vec::with_capacity(HEADER_LEN);
+        buf.push(HEADER_VERSION);
+        for field in [
+            self.size.rows(),
+            self.size.cols(),
+            self.size.xpixel(),
+            self.size.ypixel(),
+        ] {
+            buf.extend_from_slice(&field.to_ne_bytes());
+        }
+        // pid 0 can't refer to a child process
+        buf.extend_from_slice(&self.child_pid.unwrap_or(0).to_ne_bytes());
+        buf.extend_from_slice(&self.pts_index.to_ne_bytes());
+        for flags in [
+            termios.input_modes.bits(),
+            termios.output_modes.bits(),
+            termios.control_modes.bits(),
+            termios.local_modes.bits(),
+        ] {
+            buf.extend_from_slice(&u64::from(flags).to_ne_bytes());
+        }
+        for cc in crate::ControlChar::ALL {
+            buf.push(self.termios.control_char(cc));
+        }
+        buf
+    }
+
+    fn decode(buf: &[u8], pty: &crate::sys::Pty) -> std::io::Result<Self> {
+        let invalid = || {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "invalid pty metadata received",
+            )
+        };
+        if buf.len() != HEADER_LEN || buf[0] != HEADER_VERSION {
+            return Err(invalid());
+        }
+        let mut buf = &buf[1..];
+        let rows = u16::from_ne_bytes(take(&mut buf));
+        let cols = u16::from_ne_bytes(take(&mut buf));
+        let xpixel = u16::from_ne_bytes(take(&mut buf));
+        let ypixel = u16::from_ne_bytes(take(&mut buf));
+        let child_pid = u32::from_ne_bytes(take(&mut buf));
+        let pts_index = u32::from_ne_bytes(take(&mut buf));
+        let iflag = u64::from_ne_bytes(take(&mut buf));
+        let oflag = u64::from_ne_bytes(take(&mut buf));
+        let cflag = u64::from_ne_bytes(take(&mut buf));
+        let lflag = u64::from_ne_bytes(take(&mut buf));
+        let ccs: [u8; 16] = take(&mut buf);
+
+        // start from the pty's current attributes, which will include the
+        // settings (such as the line speed) which aren't sent
+        let mut termios = pty.termios().map_err(|_| invalid())?;
+        termios.0.input_modes = rustix::termios::InputModes::from_bits_retain(
+            iflag.try_into().map_err(|_| invalid())?,
+        );
+        termios.0.output_modes =
+            rustix::termios::OutputModes::from_bits_retain(
+                oflag.try_into().map_err(|_| invalid())?,
+            );
+        termios.0.control_modes =
+            rustix::termios::ControlModes::from_bits_retain(
+                cflag.try_into().map_err(|_| invalid())?,
+            );
+        termios.0.local_modes = rustix::termios::LocalModes::from_bits_retain(
+            lflag.try_into().map_err(|_| invalid())?,
+        );
+        for (cc, value) in crate::ControlChar::ALL.into_iter().zip(ccs) {
+            termios.set_control_char(cc, value);
+        }
+
+        Ok(Self {
+            size: crate::Size::new_with_pixel(rows, cols, xpixel, ypixel),
+            termios,
+            child_pid: (child_pid != 0).then_some(child_pid),
+            pts_index,
+        })
+    }
+}
+
+/// Removes the first `N` bytes from `buf` and returns them. The length of
+/// `buf` must have already been checked.
+fn take<const N: usize>(buf: &mut &[u8]) -> [u8; N] {
+    let (field, rest) = buf.split_at(N);
+    *buf = rest;
+    let mut bytes = [0; N];
+    bytes.copy_from_slice(field);
+    bytes
+}
+
+/// Sends the given pty over a unix socket, along with its metadata.
+pub fn send(
+    socket: std::os::fd::BorrowedFd<'_>,
+    pty: &crate::sys::Pty,
+    metadata: &PtyMetadata,
+) -> std::io::Result<()> {
+    let header = metadata.encode();
+    let fds = [std::os::fd::AsFd::as_fd(pty)];
+    let mut space = [0; rustix::cmsg_space!(ScmRights(1))];
+    let mut control = rustix::net::SendAncillaryBuffer::new(&mut space);
+    control.push(rustix::net::SendAncillaryMessage::ScmRights(&fds));
+    let bytes = rustix::net::sendmsg(
+        socket,
+        &[std::io::IoSlice::new(&header)],
+        &mut control,
+        rustix::net::SendFlags::empty(),
+    )?;
+    // the fd is attached to the first byte, so we can't just retry with
+    // the rest of the header
+    if bytes != header.len() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::WriteZero,
+            "failed to send the complete pty metadata",
+        ));
+    }
+    Ok(())
+}
+
+/// Receives a pty sent by [`send`] from a unix socket, along with its
+/// metadata.
+pub fn recv(
+    socket: std::os::fd::BorrowedFd<'_>,
+) -> std::io::Result<(crate::sys::Pty, PtyMetadata)> {
+    let mut header = [0; HEADER_LEN];
+    let mut space = [0; rustix::cmsg_space!(ScmRights(1))];
+    let mut control = rustix::net::RecvAncillaryBuffer::new(&mut space);
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd",
+        target_os = "dragonfly"
+    ))]
+    let flags = rustix::net::RecvFlags::CMSG_CLOEXEC;
+    #[cfg(not(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd",
+        target_os = "dragonfly"
+    )))]
+    let flags = rustix::net::RecvFlags::empty();
+    let msg = rustix::net::recvmsg(
+        socket,
+        &mut [std::io::IoSliceMut::new(&mut header)],
+        &mut control,
+        flags,
+    )?;
+
+    let mut fds = vec![];
+    for message in control.drain() {
+        if let rustix::net::RecvAncillaryMessage::ScmRights(received) =
+            message
+        {
+            fds.extend(received);
+        }
+    }
+    if msg.bytes == 0 {
+        return Err(std::io::ErrorKind::UnexpectedEof.into());
+    }
+    // any unexpected extra fds are closed when the vec is dropped
+    let Some(fd) = fds.into_iter().next() else {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "no pty received",
+        ));
+    };
+    #[cfg(not(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd",
+        target_os = "dragonfly"
+    )))]
+    {
+        let mut flags = rustix::io::fcntl_getfd(&fd)?;
+        flags |= rustix::io::FdFlags::CLOEXEC;
+        rustix::io::fcntl_setfd(&fd, flags)?;
+    }
+
+    let pty = crate::sys::Pty::from_fd(fd);
+    let metadata = PtyMetadata::decode(&header[..msg.bytes], &pty)?;
+    Ok((pty, metadata))
+}
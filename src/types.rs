@@ -1,5 +1,9 @@
 /// Represents the size of the pty.
-#[derive(Debug, Clone, Copy)]
+///
+/// The [`Display`](std::fmt::Display) and [`FromStr`](std::str::FromStr)
+/// implementations use the conventional `COLSxROWS` format (for instance,
+/// `80x24`), ignoring the pixel dimensions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Size {
     row: u16,
     col: u16,
@@ -36,6 +40,30 @@ impl Size {
             ypixel,
         }
     }
+
+    /// Returns the number of rows.
+    #[must_use]
+    pub fn rows(&self) -> u16 {
+        self.row
+    }
+
+    /// Returns the number of columns.
+    #[must_use]
+    pub fn cols(&self) -> u16 {
+        self.col
+    }
+
+    /// Returns the width in pixels.
+    #[must_use]
+    pub fn xpixel(&self) -> u16 {
+        self.xpixel
+    }
+
+    /// Returns the height in pixels.
+    #[must_use]
+    pub fn ypixel(&self) -> u16 {
+        self.ypixel
+    }
 }
 
 impl From<Size> for libc::winsize {
@@ -48,3 +76,393 @@ impl From<Size> for libc::winsize {
         }
     }
 }
+
+impl From<libc::winsize> for Size {
+    fn from(size: libc::winsize) -> Self {
+        Self {
+            row: size.ws_row,
+            col: size.ws_col,
+            xpixel: size.ws_xpixel,
+            ypixel: size.ws_ypixel,
+        }
+    }
+}
+
+impl std::fmt::Display for Size {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}x{}", self.col, self.row)
+    }
+}
+
+impl std::str::FromStr for Size {
+    type Err = ParseSizeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (col, row) = s.split_once('x').ok_or(ParseSizeError(()))?;
+        let col = col.parse().map_err(|_| ParseSizeError(()))?;
+        let row = row.parse().map_err(|_| ParseSizeError(()))?;
+        Ok(Self::new(row, col))
+    }
+}
+
+/// Error returned when parsing a [`Size`] from a string fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseSizeError(());
+
+impl std::fmt::Display for ParseSizeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid size (expected a value like \"80x24\")")
+    }
+}
+
+impl std::error::Error for ParseSizeError {}
+
+/// Represents the terminal attributes (termios settings) of the pty.
+///
+/// Obtain an instance via [`Pty::termios`](crate::Pty::termios), modify it
+/// through the accessors below, and apply it with
+/// [`Pty::set_termios`](crate::Pty::set_termios).
+#[derive(Debug, Clone)]
+pub struct Termios(pub(crate) rustix::termios::Termios);
+
+impl Termios {
+    /// Returns whether input characters are echoed (`ECHO`).
+    #[must_use]
+    pub fn echo(&self) -> bool {
+        self.0
+            .local_modes
+            .contains(rustix::termios::LocalModes::ECHO)
+    }
+
+    /// Sets whether input characters are echoed (`ECHO`).
+    pub fn set_echo(&mut self, echo: bool) {
+        self.0
+            .local_modes
+            .set(rustix::termios::LocalModes::ECHO, echo);
+    }
+
+    /// Returns whether canonical (line-buffered) input processing is
+    /// enabled (`ICANON`).
+    #[must_use]
+    pub fn canonical(&self) -> bool {
+        self.0
+            .local_modes
+            .contains(rustix::termios::LocalModes::ICANON)
+    }
+
+    /// Sets whether canonical (line-buffered) input processing is enabled
+    /// (`ICANON`).
+    pub fn set_canonical(&mut self, canonical: bool) {
+        self.0
+            .local_modes
+            .set(rustix::termios::LocalModes::ICANON, canonical);
+    }
+
+    /// Returns whether the `INTR`, `QUIT`, and `SUSP` characters generate
+    /// signals (`ISIG`).
+    #[must_use]
+    pub fn isig(&self) -> bool {
+        self.0
+            .local_modes
+            .contains(rustix::termios::LocalModes::ISIG)
+    }
+
+    /// Sets whether the `INTR`, `QUIT`, and `SUSP` characters generate
+    /// signals (`ISIG`).
+    pub fn set_isig(&mut self, isig: bool) {
+        self.0
+            .local_modes
+            .set(rustix::termios::LocalModes::ISIG, isig);
+    }
+
+    /// Returns whether implementation-defined input processing is enabled
+    /// (`IEXTEN`).
+    #[must_use]
+    pub fn iexten(&self) -> bool {
+        self.0
+            .local_modes
+            .contains(rustix::termios::LocalModes::IEXTEN)
+    }
+
+    /// Sets whether implementation-defined input processing is enabled
+    /// (`IEXTEN`).
+    pub fn set_iexten(&mut self, iexten: bool) {
+        self.0
+            .local_modes
+            .set(rustix::termios::LocalModes::IEXTEN, iexten);
+    }
+
+    /// Returns whether input editing is performed externally (`EXTPROC`),
+    /// for instance by the client of a remote terminal. While this is
+    /// enabled on a pty in packet mode, every change to the terminal
+    /// attributes is reported as
+    /// [`PacketEvent::IoctlChanged`](crate::packet::PacketEvent::IoctlChanged).
+    #[cfg(not(any(
+        target_os = "aix",
+        target_os = "haiku",
+        target_os = "nto",
+        target_os = "redox"
+    )))]
+    #[must_use]
+    pub fn extproc(&self) -> bool {
+        self.0
+            .local_modes
+            .contains(rustix::termios::LocalModes::EXTPROC)
+    }
+
+    /// Sets whether input editing is performed externally (`EXTPROC`).
+    #[cfg(not(any(
+        target_os = "aix",
+        target_os = "haiku",
+        target_os = "nto",
+        target_os = "redox"
+    )))]
+    pub fn set_extproc(&mut self, extproc: bool) {
+        self.0
+            .local_modes
+            .set(rustix::termios::LocalModes::EXTPROC, extproc);
+    }
+
+    /// Returns whether input is treated as UTF-8 for the purposes of
+    /// character erase in canonical mode (`IUTF8`).
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "ios"
+    ))]
+    #[must_use]
+    pub fn iutf8(&self) -> bool {
+        self.0
+            .input_modes
+            .contains(rustix::termios::InputModes::IUTF8)
+    }
+
+    /// Sets whether input is treated as UTF-8 for the purposes of character
+    /// erase in canonical mode (`IUTF8`).
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "ios"
+    ))]
+    pub fn set_iutf8(&mut self, iutf8: bool) {
+        self.0
+            .input_modes
+            .set(rustix::termios::InputModes::IUTF8, iutf8);
+    }
+
+    /// Returns whether carriage returns on input are translated to newlines
+    /// (`ICRNL`).
+    #[must_use]
+    pub fn icrnl(&self) -> bool {
+        self.0
+            .input_modes
+            .contains(rustix::termios::InputModes::ICRNL)
+    }
+
+    /// Sets whether carriage returns on input are translated to newlines
+    /// (`ICRNL`).
+    pub fn set_icrnl(&mut self, icrnl: bool) {
+        self.0
+            .input_modes
+            .set(rustix::termios::InputModes::ICRNL, icrnl);
+    }
+
+    /// Returns whether XON/XOFF flow control is enabled on output (`IXON`).
+    #[must_use]
+    pub fn ixon(&self) -> bool {
+        self.0
+            .input_modes
+            .contains(rustix::termios::InputModes::IXON)
+    }
+
+    /// Sets whether XON/XOFF flow control is enabled on output (`IXON`).
+    pub fn set_ixon(&mut self, ixon: bool) {
+        self.0
+            .input_modes
+            .set(rustix::termios::InputModes::IXON, ixon);
+    }
+
+    /// Returns whether implementation-defined output processing is enabled
+    /// (`OPOST`).
+    #[must_use]
+    pub fn opost(&self) -> bool {
+        self.0
+            .output_modes
+            .contains(rustix::termios::OutputModes::OPOST)
+    }
+
+    /// Sets whether implementation-defined output processing is enabled
+    /// (`OPOST`).
+    pub fn set_opost(&mut self, opost: bool) {
+        self.0
+            .output_modes
+            .set(rustix::termios::OutputModes::OPOST, opost);
+    }
+
+    /// Returns whether newlines on output are translated to carriage return
+    /// followed by newline (`ONLCR`).
+    #[must_use]
+    pub fn onlcr(&self) -> bool {
+        self.0
+            .output_modes
+            .contains(rustix::termios::OutputModes::ONLCR)
+    }
+
+    /// Sets whether newlines on output are translated to carriage return
+    /// followed by newline (`ONLCR`).
+    pub fn set_onlcr(&mut self, onlcr: bool) {
+        self.0
+            .output_modes
+            .set(rustix::termios::OutputModes::ONLCR, onlcr);
+    }
+
+    /// Returns the minimum number of bytes for a noncanonical read
+    /// (`VMIN`).
+    #[must_use]
+    pub fn vmin(&self) -> u8 {
+        self.control_char(ControlChar::Min)
+    }
+
+    /// Sets the minimum number of bytes for a noncanonical read (`VMIN`).
+    pub fn set_vmin(&mut self, vmin: u8) {
+        self.set_control_char(ControlChar::Min, vmin);
+    }
+
+    /// Returns the timeout for a noncanonical read, in tenths of a second
+    /// (`VTIME`).
+    #[must_use]
+    pub fn vtime(&self) -> u8 {
+        self.control_char(ControlChar::Time)
+    }
+
+    /// Sets the timeout for a noncanonical read, in tenths of a second
+    /// (`VTIME`).
+    pub fn set_vtime(&mut self, vtime: u8) {
+        self.set_control_char(ControlChar::Time, vtime);
+    }
+
+    /// Returns the value of the given control character.
+    #[must_use]
+    pub fn control_char(&self, cc: ControlChar) -> u8 {
+        self.0.special_codes[cc.index()]
+    }
+
+    /// Sets the value of the given control character.
+    pub fn set_control_char(&mut self, cc: ControlChar, value: u8) {
+        self.0.special_codes[cc.index()] = value;
+    }
+
+    /// Modifies these settings to put the terminal into raw mode, as with
+    /// `cfmakeraw`.
+    pub fn make_raw(&mut self) {
+        self.0.make_raw();
+    }
+}
+
+/// The control characters which can be accessed through [`Termios`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ControlChar {
+    /// `VINTR`, which sends `SIGINT` (usually `^C`)
+    Intr,
+    /// `VQUIT`, which sends `SIGQUIT` (usually `^\`)
+    Quit,
+    /// `VERASE`, which erases the previous character (usually `^?`)
+    Erase,
+    /// `VKILL`, which erases the current line (usually `^U`)
+    Kill,
+    /// `VEOF`, which signals end of file (usually `^D`)
+    Eof,
+    /// `VEOL`, an additional end of line character
+    Eol,
+    /// `VEOL2`, another additional end of line character
+    Eol2,
+    /// `VSTART`, which restarts stopped output (usually `^Q`)
+    Start,
+    /// `VSTOP`, which stops output (usually `^S`)
+    Stop,
+    /// `VSUSP`, which sends `SIGTSTP` (usually `^Z`)
+    Susp,
+    /// `VWERASE`, which erases the previous word (usually `^W`)
+    Werase,
+    /// `VLNEXT`, which quotes the next character (usually `^V`)
+    Lnext,
+    /// `VREPRINT`, which reprints the current line (usually `^R`)
+    Reprint,
+    /// `VDISCARD`, which toggles discarding of output (usually `^O`)
+    Discard,
+    /// `VMIN`, the minimum number of bytes for a noncanonical read
+    Min,
+    /// `VTIME`, the timeout for a noncanonical read
+    Time,
+}
+
+impl ControlChar {
+    pub(crate) const ALL: [Self; 16] = [
+        Self::Intr,
+        Self::Quit,
+        Self::Erase,
+        Self::Kill,
+        Self::Eof,
+        Self::Eol,
+        Self::Eol2,
+        Self::Start,
+        Self::Stop,
+        Self::Susp,
+        Self::Werase,
+        Self::Lnext,
+        Self::Reprint,
+        Self::Discard,
+        Self::Min,
+        Self::Time,
+    ];
+
+    fn index(self) -> rustix::termios::SpecialCodeIndex {
+        use rustix::termios::SpecialCodeIndex;
+
+        match self {
+            Self::Intr => SpecialCodeIndex::VINTR,
+            Self::Quit => SpecialCodeIndex::VQUIT,
+            Self::Erase => SpecialCodeIndex::VERASE,
+            Self::Kill => SpecialCodeIndex::VKILL,
+            Self::Eof => SpecialCodeIndex::VEOF,
+            Self::Eol => SpecialCodeIndex::VEOL,
+            Self::Eol2 => SpecialCodeIndex::VEOL2,
+            Self::Start => SpecialCodeIndex::VSTART,
+            Self::Stop => SpecialCodeIndex::VSTOP,
+            Self::Susp => SpecialCodeIndex::VSUSP,
+            Self::Werase => SpecialCodeIndex::VWERASE,
+            Self::Lnext => SpecialCodeIndex::VLNEXT,
+            Self::Reprint => SpecialCodeIndex::VREPRINT,
+            Self::Discard => SpecialCodeIndex::VDISCARD,
+            Self::Min => SpecialCodeIndex::VMIN,
+            Self::Time => SpecialCodeIndex::VTIME,
+        }
+    }
+}
+
+/// When changes made via [`Pty::set_termios`](crate::Pty::set_termios)
+/// should take effect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SetArg {
+    /// The change occurs immediately (`TCSANOW`).
+    Now,
+    /// The change occurs after all output written to the pty has been
+    /// transmitted (`TCSADRAIN`).
+    Drain,
+    /// The change occurs after all output written to the pty has been
+    /// transmitted, and all input that has been received but not read is
+    /// discarded (`TCSAFLUSH`).
+    Flush,
+}
+
+impl From<SetArg> for rustix::termios::OptionalActions {
+    fn from(when: SetArg) -> Self {
+        match when {
+            SetArg::Now => Self::Now,
+            SetArg::Drain => Self::Drain,
+            SetArg::Flush => Self::Flush,
+        }
+    }
+}
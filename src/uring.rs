@@ -0,0 +1,437 @@
+//! A pty whose reads and writes are submitted via `io_uring`, using
+//! [`tokio-uring`](https://docs.rs/tokio-uring)
+//!
+//! The [`Pty`](crate::Pty) in the crate root waits for the pty to become
+//! readable and then issues a separate `read` syscall. The [`Pty`] in this
+//! module instead submits the read to the kernel directly, and completes
+//! once the data has been read into the buffer. As with `tokio-uring` in
+//! general, buffers are passed by value and handed back along with the
+//! result, and the pty must be used from within a `tokio-uring` runtime
+//! (see [`tokio_uring::start`]). Since that runtime is built on top of
+//! tokio, child processes can be spawned on the pty with
+//! [`Command`](crate::Command) as usual.
+//!
+//! Buffers can also be registered with the kernel up front via a
+//! [`FixedBufRegistry`](tokio_uring::buf::fixed::FixedBufRegistry) and
+//! used with [`read_fixed`](Pty::read_fixed) and
+//! [`write_fixed`](Pty::write_fixed), which saves the kernel from mapping
+//! the buffer for every operation.
+//!
+//! The pty is put into non-blocking mode, so that an idle pty never ties up
+//! an `io_uring` worker thread waiting for a blocking read to complete. On
+//! kernels where the pty supports non-blocking `io_uring` operations, the
+//! kernel itself waits for the pty to become ready; otherwise, the read (or
+//! write) fails with `EAGAIN`, and is retried once the tokio reactor
+//! reports the pty as ready.
+//!
+//! ```no_run
+//! # fn main() -> pty_process::Result<()> {
+//! tokio_uring::start(async {
+//!     let pty = pty_process::uring::Pty::new()?;
+//!     pty.resize(pty_process::Size::new(24, 80))?;
+//!     let mut child =
+//!         pty_process::Command::new("ls").spawn(&pty.pts()?)?;
+//!     let output = pty.drain_until_hangup().await?;
+//!     child.wait().await?;
+//!     # drop(output);
+//!     Ok(())
+//! })
+//! # }
+//! ```
+
+// futures driving tokio-uring operations are inherently tied to the thread
+// whose runtime submitted them
+#![allow(clippy::future_not_send)]
+
+/// How much to grow the buffer by when draining the pty
+const READ_SIZE: usize = 4096;
+
+/// An allocated pty whose I/O is performed via `io_uring`
+///
+/// This type is not `Send`, since operations are submitted to the
+/// `io_uring` instance of the current thread. There are no split halves as
+/// with [`Pty::split`](crate::Pty::split): every method here takes `&self`,
+/// so a read and a write can be in flight on the same `Pty` at once, and it
+/// can be shared between tasks spawned with [`tokio_uring::spawn`] by
+/// wrapping it in an [`Rc`](std::rc::Rc).
+pub struct Pty {
+    pty: tokio::io::unix::AsyncFd<crate::sys::Pty>,
+    file: tokio_uring::fs::File,
+}
+
+impl Pty {
+    /// Allocate and return a new pty.
+    ///
+    /// # Errors
+    /// Returns an error if the pty failed to be allocated, or if we were
+    /// unable to put it into non-blocking mode.
+    pub fn new() -> crate::Result<Self> {
+        Self::from_sys(crate::sys::Pty::open()?)
+    }
+
+    /// Allocate and return a new pty from the given `ptmx` device node. See
+    /// [`Pty::open_at`](crate::Pty::open_at).
+    ///
+    /// # Errors
+    /// Returns an error if the device node could not be opened, or if it
+    /// is not a `ptmx` device, or if we were unable to put it into
+    /// non-blocking mode.
+    pub fn open_at(ptmx: impl AsRef<std::path::Path>) -> crate::Result<Self> {
+        Self::from_sys(crate::sys::Pty::open_at(ptmx.as_ref())?)
+    }
+
+    fn from_sys(pty: crate::sys::Pty) -> crate::Result<Self> {
+        // `io_uring` operations go through a duplicate of the file
+        // descriptor, so that the ioctls used for everything else don't
+        // have to go through the runtime. the duplicate shares the file
+        // status flags, so it is non-blocking too.
+        pty.set_nonblocking()?;
+        let fd = rustix::io::fcntl_dupfd_cloexec(&pty, 0)?;
+        let file = tokio_uring::fs::File::from_std(std::fs::File::from(fd));
+        Ok(Self {
+            pty: tokio::io::unix::AsyncFd::new(pty)?,
+            file,
+        })
+    }
+
+    /// Change the terminal size associated with the pty.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to set the terminal size.
+    pub fn resize(&self, size: crate::Size) -> crate::Result<()> {
+        self.pty.get_ref().set_term_size(size)
+    }
+
+    /// Returns the terminal size currently associated with the pty.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to retrieve the terminal size.
+    pub fn size(&self) -> crate::Result<crate::Size> {
+        self.pty.get_ref().term_size()
+    }
+
+    /// Returns the current terminal attributes of the pty.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to retrieve the terminal
+    /// attributes.
+    pub fn termios(&self) -> crate::Result<crate::Termios> {
+        self.pty.get_ref().termios()
+    }
+
+    /// Change the terminal attributes of the pty. See [`crate::SetArg`] for
+    /// the meaning of `when`.
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to set the terminal attributes.
+    pub fn set_termios(
+        &self,
+        when: crate::SetArg,
+        termios: &crate::Termios,
+    ) -> crate::Result<()> {
+        self.pty.get_ref().set_termios(when, termios)
+    }
+
+    /// Returns the id of the foreground process group of the pty. See
+    /// [`Pty::foreground_process_group`](crate::Pty::foreground_process_group).
+    ///
+    /// # Errors
+    /// Returns an error if the foreground process group could not be
+    /// determined.
+    pub fn foreground_process_group(&self) -> crate::Result<u32> {
+        self.pty.get_ref().foreground_process_group()
+    }
+
+    /// Sends the given signal to the foreground process group of the pty
+    /// (see [`foreground_process_group`](Self::foreground_process_group)).
+    ///
+    /// # Errors
+    /// Returns an error if the foreground process group could not be
+    /// determined, or if the signal could not be sent.
+    pub fn signal_foreground(
+        &self,
+        signal: crate::Signal,
+    ) -> crate::Result<()> {
+        self.pty.get_ref().signal_foreground(signal)
+    }
+
+    /// Returns the id of the session which the pty is the controlling
+    /// terminal of.
+    ///
+    /// # Errors
+    /// Returns an error if the pty is not the controlling terminal of any
+    /// session.
+    pub fn session_id(&self) -> crate::Result<u32> {
+        self.pty.get_ref().session_id()
+    }
+
+    /// Enables or disables packet mode on the pty. See
+    /// [`Pty::set_packet_mode`](crate::Pty::set_packet_mode).
+    ///
+    /// # Errors
+    /// Returns an error if we were unable to change the packet mode.
+    pub fn set_packet_mode(&self, enabled: bool) -> crate::Result<()> {
+        self.pty.get_ref().set_packet_mode(enabled)
+    }
+
+    /// Sends the interrupt character (`VINTR`, usually `^C`) to the pty.
+    /// See [`Pty::send_interrupt`](crate::Pty::send_interrupt).
+    ///
+    /// # Errors
+    /// Returns an error if the terminal attributes could not be retrieved,
+    /// if the character is disabled, or if writing to the pty failed.
+    pub async fn send_interrupt(&self) -> crate::Result<()> {
+        self.send_control_char(crate::ControlChar::Intr).await
+    }
+
+    /// Sends the quit character (`VQUIT`, usually `^\`) to the pty. See
+    /// [`Pty::send_quit`](crate::Pty::send_quit).
+    ///
+    /// # Errors
+    /// Returns an error if the terminal attributes could not be retrieved,
+    /// if the character is disabled, or if writing to the pty failed.
+    pub async fn send_quit(&self) -> crate::Result<()> {
+        self.send_control_char(crate::ControlChar::Quit).await
+    }
+
+    /// Sends the suspend character (`VSUSP`, usually `^Z`) to the pty. See
+    /// [`Pty::send_suspend`](crate::Pty::send_suspend).
+    ///
+    /// # Errors
+    /// Returns an error if the terminal attributes could not be retrieved,
+    /// if the character is disabled, or if writing to the pty failed.
+    pub async fn send_suspend(&self) -> crate::Result<()> {
+        self.send_control_char(crate::ControlChar::Susp).await
+    }
+
+    /// Sends the end of file character (`VEOF`, usually `^D`) to the pty.
+    /// As with [`Pty::send_eof`](crate::Pty::send_eof), this must be called
+    /// a second time to signal end of file if there is unfinished input on
+    /// the current line.
+    ///
+    /// # Errors
+    /// Returns an error if the terminal attributes could not be retrieved,
+    /// if the character is disabled, or if writing to the pty failed.
+    pub async fn send_eof(&self) -> crate::Result<()> {
+        self.send_control_char(crate::ControlChar::Eof).await
+    }
+
+    /// Sends the erase character (`VERASE`, usually `^?`) to the pty. See
+    /// [`Pty::send_erase`](crate::Pty::send_erase).
+    ///
+    /// # Errors
+    /// Returns an error if the terminal attributes could not be retrieved,
+    /// if the character is disabled, or if writing to the pty failed.
+    pub async fn send_erase(&self) -> crate::Result<()> {
+        self.send_control_char(crate::ControlChar::Erase).await
+    }
+
+    /// Opens a file descriptor for the other end of the pty, which should be
+    /// attached to the child process running in it. See
+    /// [`Command::spawn`](crate::Command::spawn).
+    ///
+    /// # Errors
+    /// Returns an error if the device node to open could not be determined,
+    /// or if the device node could not be opened.
+    pub fn pts(&self) -> crate::Result<crate::Pts> {
+        Ok(crate::Pts(self.pty.get_ref().pts()?))
+    }
+
+    /// Returns the index of the pty, as used in the name of its device node
+    /// (for instance, `3` for `/dev/pts/3`). This is mainly useful for
+    /// logging.
+    ///
+    /// # Errors
+    /// Returns an error if the index could not be determined.
+    pub fn pts_index(&self) -> crate::Result<u32> {
+        self.pty.get_ref().pts_index()
+    }
+
+    /// Reads output from the pty into the start of `buf` (up to its total
+    /// capacity), returning the number of bytes read along with the buffer.
+    /// Returns 0 once every process holding the child end of the pty open
+    /// has closed it.
+    pub async fn read<T: tokio_uring::buf::BoundedBufMut>(
+        &self,
+        buf: T,
+    ) -> tokio_uring::BufResult<usize, T> {
+        // ptys aren't seekable, so the position is ignored
+        let (res, buf) = self
+            .retry(tokio::io::Interest::READABLE, buf, |buf| {
+                self.file.read_at(buf, 0)
+            })
+            .await;
+        (self.eof_on_hangup(res), buf)
+    }
+
+    /// Reads output from the pty into a buffer which has been registered
+    /// with the kernel (see
+    /// [`FixedBufRegistry`](tokio_uring::buf::fixed::FixedBufRegistry)),
+    /// as with [`read`](Self::read).
+    pub async fn read_fixed<T>(
+        &self,
+        buf: T,
+    ) -> tokio_uring::BufResult<usize, T>
+    where
+        T: tokio_uring::buf::BoundedBufMut<
+            BufMut = tokio_uring::buf::fixed::FixedBuf,
+        >,
+    {
+        let (res, buf) = self
+            .retry(tokio::io::Interest::READABLE, buf, |buf| {
+                self.file.read_fixed_at(buf, 0)
+            })
+            .await;
+        (self.eof_on_hangup(res), buf)
+    }
+
+    /// Writes the initialized contents of `buf` to the pty, returning the
+    /// number of bytes written along with the buffer.
+    pub async fn write<T: tokio_uring::buf::BoundedBuf>(
+        &self,
+        buf: T,
+    ) -> tokio_uring::BufResult<usize, T> {
+        self.retry(tokio::io::Interest::WRITABLE, buf, |buf| {
+            self.file.write_at(buf, 0).submit()
+        })
+        .await
+    }
+
+    /// Writes the initialized contents of a buffer which has been
+    /// registered with the kernel (see
+    /// [`FixedBufRegistry`](tokio_uring::buf::fixed::FixedBufRegistry)) to
+    /// the pty, as with [`write`](Self::write).
+    pub async fn write_fixed<T>(
+        &self,
+        buf: T,
+    ) -> tokio_uring::BufResult<usize, T>
+    where
+        T: tokio_uring::buf::BoundedBuf<
+            Buf = tokio_uring::buf::fixed::FixedBuf,
+        >,
+    {
+        self.retry(tokio::io::Interest::WRITABLE, buf, |buf| {
+            self.file.write_fixed_at(buf, 0)
+        })
+        .await
+    }
+
+    /// Writes the entire initialized contents of `buf` to the pty, returning
+    /// the buffer once it has all been written.
+    pub async fn write_all<T: tokio_uring::buf::IoBuf>(
+        &self,
+        buf: T,
+    ) -> tokio_uring::BufResult<(), T> {
+        let len = buf.bytes_init();
+        let mut buf = buf;
+        let mut written = 0;
+        while written < len {
+            let (res, slice) = self
+                .write(tokio_uring::buf::BoundedBuf::slice(buf, written..len))
+                .await;
+            buf = slice.into_inner();
+            match res {
+                Ok(0) => {
+                    return (Err(std::io::ErrorKind::WriteZero.into()), buf)
+                }
+                Ok(bytes) => written += bytes,
+                Err(e) => return (Err(e), buf),
+            }
+        }
+        (Ok(()), buf)
+    }
+
+    /// Reads all remaining output from the pty until every process holding
+    /// the child end of the pty open has closed it (typically, when the
+    /// child process and any other processes in its session have exited),
+    /// and returns it.
+    ///
+    /// Note that this will never return if the [`Pts`](crate::Pts) used to
+    /// spawn the child is still open.
+    ///
+    /// # Errors
+    /// Returns an error if reading from the pty failed.
+    pub async fn drain_until_hangup(&self) -> crate::Result<Vec<u8>> {
+        let mut output = Vec::with_capacity(READ_SIZE);
+        loop {
+            if output.len() == output.capacity() {
+                output.reserve(READ_SIZE);
+            }
+            let len = output.len();
+            let (res, slice) = self
+                .read(tokio_uring::buf::BoundedBuf::slice(output, len..))
+                .await;
+            output = slice.into_inner();
+            if res? == 0 {
+                return Ok(output);
+            }
+        }
+    }
+
+    async fn send_control_char(
+        &self,
+        cc: crate::ControlChar,
+    ) -> crate::Result<()> {
+        let byte = self.pty.get_ref().control_char(cc)?;
+        let (res, _) = self.write_all(vec![byte]).await;
+        Ok(res?)
+    }
+
+    /// Submits `op`, and if it fails with `EAGAIN` (which only happens on
+    /// kernels where the pty doesn't support non-blocking `io_uring`
+    /// operations), waits for the pty to become ready and submits it again.
+    async fn retry<T, F, Fut>(
+        &self,
+        interest: tokio::io::Interest,
+        mut buf: T,
+        op: F,
+    ) -> tokio_uring::BufResult<usize, T>
+    where
+        F: Fn(T) -> Fut,
+        Fut: std::future::Future<Output = tokio_uring::BufResult<usize, T>>,
+    {
+        loop {
+            let (res, returned) = op(buf).await;
+            buf = returned;
+            match res {
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                    // readiness is cleared before retrying, so a wakeup
+                    // which arrives in between isn't lost
+                    match self.pty.ready(interest).await {
+                        Ok(mut guard) => guard.clear_ready(),
+                        Err(e) => return (Err(e), buf),
+                    }
+                }
+                res => return (res, buf),
+            }
+        }
+    }
+
+    fn eof_on_hangup(
+        &self,
+        res: std::io::Result<usize>,
+    ) -> std::io::Result<usize> {
+        match res {
+            // see sys::read
+            Err(e)
+                if e.raw_os_error() == Some(libc::EIO)
+                    && self.pty.get_ref().is_hung_up() =>
+            {
+                Ok(0)
+            }
+            res => res,
+        }
+    }
+}
+
+impl std::os::fd::AsFd for Pty {
+    fn as_fd(&self) -> std::os::fd::BorrowedFd<'_> {
+        self.pty.get_ref().as_fd()
+    }
+}
+
+impl std::os::fd::AsRawFd for Pty {
+    fn as_raw_fd(&self) -> std::os::fd::RawFd {
+        self.pty.get_ref().as_raw_fd()
+    }
+}
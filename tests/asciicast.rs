@@ -0,0 +1,239 @@
+#[cfg(feature = "asciicast")]
+#[test]
+fn test_asciicast_blocking() {
+    use std::io::{Read as _, Write as _};
+
+    let pty = pty_process::blocking::Pty::new().unwrap();
+    let pts = pty.pts().unwrap();
+    pty.resize(pty_process::Size::new(24, 80)).unwrap();
+    let mut child = pty_process::blocking::Command::new("perl")
+        .args([
+            "-E",
+            "$|++; $SIG{WINCH} = sub { say 'WINCH' }; say 'started'; \
+             $_ = <>; say 'done'",
+        ])
+        .spawn(&pts)
+        .unwrap();
+    drop(pts);
+
+    let mut termios = pty.termios().unwrap();
+    termios.set_echo(false);
+    pty.set_termios(pty_process::SetArg::Now, &termios).unwrap();
+
+    let mut recording =
+        pty_process::blocking::asciicast::Recording::new(pty, vec![])
+            .unwrap();
+    recording.set_record_input(true);
+
+    let mut buf = [0_u8; 4096];
+    nix::unistd::alarm::set(5);
+    let bytes = recording.read(&mut buf).unwrap();
+    assert_eq!(&buf[..bytes], b"started\r\n");
+    recording.resize(pty_process::Size::new(25, 81)).unwrap();
+    let bytes = recording.read(&mut buf).unwrap();
+    assert_eq!(&buf[..bytes], b"WINCH\r\n");
+    recording.write_all(b"go\n").unwrap();
+    while recording.read(&mut buf).unwrap() > 0 {}
+    nix::unistd::alarm::cancel();
+
+    let status = child.wait().unwrap();
+    assert_eq!(status.code().unwrap(), 0);
+
+    let (_, cast) = recording.into_inner();
+    let mut player =
+        pty_process::blocking::asciicast::Player::new(&cast[..]).unwrap();
+    assert_eq!(player.header().size(), pty_process::Size::new(24, 80));
+    assert!(player.header().timestamp().is_some());
+
+    let events: Vec<_> =
+        player.by_ref().map(|event| event.unwrap().1).collect();
+    assert_eq!(
+        events,
+        [
+            pty_process::asciicast::Event::Output(b"started\r\n".to_vec()),
+            pty_process::asciicast::Event::Resize(pty_process::Size::new(
+                25, 81
+            )),
+            pty_process::asciicast::Event::Output(b"WINCH\r\n".to_vec()),
+            pty_process::asciicast::Event::Input(b"go\n".to_vec()),
+            pty_process::asciicast::Event::Output(b"done\r\n".to_vec()),
+        ]
+    );
+
+    let mut output = vec![];
+    pty_process::blocking::asciicast::Player::new(&cast[..])
+        .unwrap()
+        .play(&mut output, pty_process::asciicast::Speed::Instant)
+        .unwrap();
+    assert_eq!(output, b"started\r\nWINCH\r\ndone\r\n");
+}
+
+#[cfg(feature = "asciicast")]
+#[test]
+fn test_asciicast_utf8() {
+    let mut recorder = pty_process::asciicast::Recorder::new(
+        vec![],
+        pty_process::Size::new(24, 80),
+    )
+    .unwrap();
+    recorder.record_output(b"h\xc3").unwrap();
+    recorder.record_output(b"\xa9llo \xff").unwrap();
+    let cast = recorder.into_inner();
+
+    let events: Vec<_> =
+        pty_process::blocking::asciicast::Player::new(&cast[..])
+            .unwrap()
+            .map(|event| event.unwrap().1)
+            .collect();
+    assert_eq!(
+        events,
+        [
+            pty_process::asciicast::Event::Output(b"h".to_vec()),
+            pty_process::asciicast::Event::Output(
+                "\u{e9}llo \u{fffd}".as_bytes().to_vec()
+            ),
+        ]
+    );
+}
+
+#[cfg(feature = "asciicast")]
+#[test]
+fn test_asciicast_parse() {
+    let cast = br#"{"version": 2, "width": 80, "height": 24}
+[0.5, "o", "foo"]
+[1.0, "r", "100x30"]
+[1.25, "m", "chapter"]
+"#;
+    let mut player =
+        pty_process::blocking::asciicast::Player::new(&cast[..]).unwrap();
+    assert_eq!(player.header().size(), pty_process::Size::new(24, 80));
+    assert_eq!(player.header().timestamp(), None);
+    let events: Vec<_> = player.by_ref().map(Result::unwrap).collect();
+    assert_eq!(
+        events,
+        [
+            (
+                std::time::Duration::from_millis(500),
+                pty_process::asciicast::Event::Output(b"foo".to_vec())
+            ),
+            (
+                std::time::Duration::from_secs(1),
+                pty_process::asciicast::Event::Resize(
+                    pty_process::Size::new(30, 100)
+                )
+            ),
+            (
+                std::time::Duration::from_millis(1250),
+                pty_process::asciicast::Event::Marker("chapter".to_string())
+            ),
+        ]
+    );
+
+    assert!(pty_process::blocking::asciicast::Player::new(
+        &br#"{"version": 1, "width": 80, "height": 24}"#[..]
+    )
+    .is_err());
+}
+
+#[cfg(all(feature = "async", feature = "asciicast"))]
+#[tokio::test]
+async fn test_asciicast_async() {
+    use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
+
+    let pty = pty_process::Pty::new().unwrap();
+    let pts = pty.pts().unwrap();
+    pty.resize(pty_process::Size::new(24, 80)).unwrap();
+    let mut child = pty_process::Command::new("echo")
+        .arg("foo")
+        .spawn(&pts)
+        .unwrap();
+    drop(pts);
+
+    let mut recording =
+        pty_process::asciicast::Recording::new(pty, vec![]).unwrap();
+    let mut buf = [0_u8; 4096];
+    tokio::time::timeout(std::time::Duration::from_secs(5), async {
+        while recording.read(&mut buf).await.unwrap() > 0 {}
+    })
+    .await
+    .unwrap();
+
+    let status = child.wait().await.unwrap();
+    assert_eq!(status.code().unwrap(), 0);
+
+    recording.flush().await.unwrap();
+    let (_, cast) = recording.into_inner();
+    let mut output = vec![];
+    pty_process::asciicast::Player::new(&cast[..])
+        .await
+        .unwrap()
+        .play(&mut output, pty_process::asciicast::Speed::Scaled(2.0))
+        .await
+        .unwrap();
+    assert_eq!(output, b"foo\r\n");
+}
+
+#[cfg(all(feature = "async", feature = "asciicast"))]
+#[tokio::test]
+async fn test_asciicast_async_stream() {
+    use tokio::io::AsyncWriteExt as _;
+
+    // the events are only written as the player consumes them, which can
+    // only happen on this single threaded runtime if reading doesn't block
+    let (reader, mut writer) = tokio::io::duplex(64);
+    let feed = tokio::spawn(async move {
+        writer
+            .write_all(b"{\"version\": 2, \"width\": 80, \"height\": 24}\n")
+            .await
+            .unwrap();
+        for i in 0..3 {
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            writer
+                .write_all(format!("[0.0, \"o\", \"{i}\"]\n").as_bytes())
+                .await
+                .unwrap();
+        }
+    });
+
+    let mut output = vec![];
+    tokio::time::timeout(std::time::Duration::from_secs(5), async {
+        let mut player = pty_process::asciicast::Player::new(
+            tokio::io::BufReader::new(reader),
+        )
+        .await
+        .unwrap();
+        assert_eq!(player.header().size(), pty_process::Size::new(24, 80));
+        player
+            .play(&mut output, pty_process::asciicast::Speed::Instant)
+            .await
+            .unwrap();
+    })
+    .await
+    .unwrap();
+    feed.await.unwrap();
+    assert_eq!(output, b"012");
+}
+
+#[cfg(feature = "asciicast")]
+#[test]
+fn test_asciicast_invalid_speed() {
+    let cast = concat!(
+        r#"{"version": 2, "width": 80, "height": 24}"#,
+        "\n",
+        r#"[0.5, "o", "foo"]"#,
+        "\n",
+    );
+    for factor in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+        let mut output = vec![];
+        let err =
+            pty_process::blocking::asciicast::Player::new(cast.as_bytes())
+                .unwrap()
+                .play(
+                    &mut output,
+                    pty_process::asciicast::Speed::Scaled(factor),
+                )
+                .unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        assert!(output.is_empty());
+    }
+}
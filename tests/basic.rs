@@ -21,52 +21,121 @@ fn test_cat_blocking() {
     assert_eq!(status.code().unwrap(), 0);
 }
 
-#[cfg(feature = "async")]
-#[tokio::test]
-async fn test_cat_async() {
+#[test]
+fn test_eof_blocking() {
+    use std::io::Read as _;
+
+    let mut pty = pty_process::blocking::Pty::new().unwrap();
+    let pts = pty.pts().unwrap();
+    pty.resize(pty_process::Size::new(24, 80)).unwrap();
+    let mut child = pty_process::blocking::Command::new("echo")
+        .arg("foo")
+        .spawn(&pts)
+        .unwrap();
+    drop(pts);
+
+    let mut output = vec![];
+    nix::unistd::alarm::set(5);
+    pty.read_to_end(&mut output).unwrap();
+    nix::unistd::alarm::cancel();
+    assert_eq!(output, b"foo\r\n");
+    // reading again after the pty has been closed still returns eof
+    assert_eq!(pty.read(&mut [0; 16]).unwrap(), 0);
+
+    let status = child.wait().unwrap();
+    assert_eq!(status.code().unwrap(), 0);
+}
+
+async_test!(test_cat_async, test_cat_async_io, {
     use futures::stream::StreamExt as _;
-    use tokio::io::AsyncWriteExt as _;
 
-    let mut pty = pty_process::Pty::new().unwrap();
+    let mut pty = backend::Pty::new().unwrap();
     let pts = pty.pts().unwrap();
     pty.resize(pty_process::Size::new(24, 80)).unwrap();
-    let mut child = pty_process::Command::new("cat").spawn(&pts).unwrap();
+    let mut child = backend::Command::new("cat").spawn(&pts).unwrap();
 
     let (pty_r, mut pty_w) = pty.split();
 
-    pty_w.write_all(b"foo\n").await.unwrap();
+    backend::write_all(&mut pty_w, b"foo\n").await;
 
-    let mut output = helpers::output_async(pty_r);
+    let mut output = backend::output(pty_r);
     assert_eq!(output.next().await.unwrap(), "foo\r\n");
     assert_eq!(output.next().await.unwrap(), "foo\r\n");
 
-    pty_w.write_all(&[4u8]).await.unwrap();
-    let status = child.wait().await.unwrap();
+    backend::write_all(&mut pty_w, &[4u8]).await;
+    let status = backend::wait(&mut child).await;
     assert_eq!(status.code().unwrap(), 0);
-}
+});
 
 #[cfg(feature = "async")]
 #[tokio::test]
-async fn test_yes_async() {
-    use tokio::io::AsyncReadExt as _;
+async fn test_cat_vectored_async() {
+    use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
 
     let mut pty = pty_process::Pty::new().unwrap();
     let pts = pty.pts().unwrap();
     pty.resize(pty_process::Size::new(24, 80)).unwrap();
-    let mut child = pty_process::Command::new("yes").spawn(&pts).unwrap();
+    let mut child = pty_process::Command::new("cat").spawn(&pts).unwrap();
+
+    assert!(tokio::io::AsyncWrite::is_write_vectored(&pty));
+    let bufs = [std::io::IoSlice::new(b"fo"), std::io::IoSlice::new(b"o\n")];
+    let bytes = pty.write_vectored(&bufs).await.unwrap();
+    assert_eq!(bytes, 4);
+
+    // reading into spare capacity never initializes the buffer up front
+    let mut output = Vec::with_capacity(64);
+    while output.len() < 10 {
+        tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            pty.read_buf(&mut output),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    }
+    assert_eq!(output, b"foo\r\nfoo\r\n");
+
+    pty.write_all(&[4u8]).await.unwrap();
+    let status = child.wait().await.unwrap();
+    assert_eq!(status.code().unwrap(), 0);
+}
+
+async_test!(test_yes_async, test_yes_async_io, {
+    let mut pty = backend::Pty::new().unwrap();
+    let pts = pty.pts().unwrap();
+    pty.resize(pty_process::Size::new(24, 80)).unwrap();
+    let mut child = backend::Command::new("yes").spawn(&pts).unwrap();
 
     let mut buf = [0u8; 3];
 
-    let bytes = pty.read_buf(&mut &mut buf[..]).await.unwrap();
+    let bytes = backend::read(&mut pty, &mut buf).await;
     assert_eq!(&buf[..bytes], b"y\r\n");
 
     let (mut pty_r, _pty_w) = pty.split();
-    let bytes = pty_r.read_buf(&mut &mut buf[..]).await.unwrap();
+    let bytes = backend::read(&mut pty_r, &mut buf).await;
     assert_eq!(&buf[..bytes], b"y\r\n");
 
     let (mut pty_r, _pty_w) = pty.into_split();
-    let bytes = pty_r.read_buf(&mut &mut buf[..]).await.unwrap();
+    let bytes = backend::read(&mut pty_r, &mut buf).await;
     assert_eq!(&buf[..bytes], b"y\r\n");
 
-    child.kill().await.unwrap()
-}
+    backend::kill(&mut child).await;
+});
+
+async_test!(test_eof_async, test_eof_async_io, {
+    let pty = backend::Pty::new().unwrap();
+    let pts = pty.pts().unwrap();
+    pty.resize(pty_process::Size::new(24, 80)).unwrap();
+    let mut child = backend::Command::new("echo")
+        .arg("foo")
+        .spawn(&pts)
+        .unwrap();
+    drop(pts);
+
+    let (mut pty_r, _pty_w) = pty.into_split();
+    let output = backend::read_to_end(&mut pty_r).await;
+    assert_eq!(output, b"foo\r\n");
+
+    let status = backend::wait(&mut child).await;
+    assert_eq!(status.code().unwrap(), 0);
+});
@@ -0,0 +1,88 @@
+mod helpers;
+
+#[test]
+fn test_builder_blocking() {
+    let pty = pty_process::blocking::Pty::builder()
+        .size(pty_process::Size::new(30, 100))
+        .echo(false)
+        .build()
+        .unwrap();
+    assert_eq!(pty.size().unwrap(), pty_process::Size::new(30, 100));
+    assert!(!pty.termios().unwrap().echo());
+    assert!(pty.termios().unwrap().canonical());
+
+    let pts = pty.pts().unwrap();
+    let mut child = pty_process::blocking::Command::new("sh")
+        .args(["-c", "stty size; stty -a | grep -cw -- -echo"])
+        .spawn(&pts)
+        .unwrap();
+
+    let mut output = helpers::output(&pty);
+    assert_eq!(output.next().unwrap(), "30 100\r\n");
+    assert_eq!(output.next().unwrap(), "1\r\n");
+
+    let status = child.wait().unwrap();
+    assert_eq!(status.code().unwrap(), 0);
+}
+
+#[test]
+fn test_builder_raw_blocking() {
+    let mut termios = pty_process::blocking::Pty::new()
+        .unwrap()
+        .termios()
+        .unwrap();
+    termios.set_control_char(pty_process::ControlChar::Intr, b'X' & 0x1f);
+
+    let pty = pty_process::blocking::Pty::builder()
+        .termios(termios)
+        .raw(true)
+        .build()
+        .unwrap();
+    let termios = pty.termios().unwrap();
+    assert!(!termios.canonical());
+    assert!(!termios.echo());
+    assert_eq!(
+        termios.control_char(pty_process::ControlChar::Intr),
+        b'X' & 0x1f
+    );
+}
+
+#[test]
+fn test_builder_nonblocking() {
+    use std::io::Read as _;
+
+    let mut pty = pty_process::blocking::Pty::builder()
+        .nonblocking(true)
+        .build()
+        .unwrap();
+    let _pts = pty.pts().unwrap();
+    let mut buf = [0_u8; 16];
+    assert_eq!(
+        pty.read(&mut buf).unwrap_err().kind(),
+        std::io::ErrorKind::WouldBlock
+    );
+}
+
+async_test!(test_builder_async, test_builder_async_io, {
+    use futures::stream::StreamExt as _;
+
+    let pty = backend::Pty::builder()
+        .size(pty_process::Size::new(30, 100))
+        .echo(false)
+        .build()
+        .unwrap();
+    assert_eq!(pty.size().unwrap(), pty_process::Size::new(30, 100));
+    assert!(!pty.termios().unwrap().echo());
+
+    let pts = pty.pts().unwrap();
+    let mut child = backend::Command::new("stty")
+        .args(["size"])
+        .spawn(&pts)
+        .unwrap();
+
+    let mut output = backend::output(pty);
+    assert_eq!(output.next().await.unwrap(), "30 100\r\n");
+
+    let status = backend::wait(&mut child).await;
+    assert_eq!(status.code().unwrap(), 0);
+});
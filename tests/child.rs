@@ -0,0 +1,196 @@
+mod helpers;
+
+#[test]
+fn test_pty_child_blocking() {
+    use std::io::Write as _;
+
+    let mut child = pty_process::blocking::Command::new("sh")
+        .args(["-c", "stty size; read x; echo \"got $x\""])
+        .spawn_pty(pty_process::Size::new(24, 80))
+        .unwrap();
+    assert!(child.id() > 0);
+    assert_eq!(child.size().unwrap(), pty_process::Size::new(24, 80));
+
+    let mut output = helpers::output(child.pty());
+    assert_eq!(output.next().unwrap(), "24 80\r\n");
+    (&child).write_all(b"foo\n").unwrap();
+    assert_eq!(output.next().unwrap(), "foo\r\n");
+    assert_eq!(output.next().unwrap(), "got foo\r\n");
+
+    let status = child.wait().unwrap();
+    assert_eq!(status.code().unwrap(), 0);
+}
+
+#[test]
+fn test_pty_child_kill_blocking() {
+    let mut child = pty_process::blocking::Command::new("sleep")
+        .arg("500")
+        .spawn_pty(pty_process::Size::new(24, 80))
+        .unwrap();
+    assert!(child.try_wait().unwrap().is_none());
+    child.kill().unwrap();
+    let status = child.wait().unwrap();
+    assert!(!status.success());
+}
+
+#[test]
+fn test_pty_child_drop_blocking() {
+    use std::os::unix::process::ExitStatusExt as _;
+
+    let child = pty_process::blocking::Command::new("sleep")
+        .arg("500")
+        .spawn_pty(pty_process::Size::new(24, 80))
+        .unwrap();
+    let (pty, mut child) = child.into_parts();
+    // closing the pty hangs up the child's session
+    drop(pty);
+    nix::unistd::alarm::set(5);
+    let status = child.wait().unwrap();
+    nix::unistd::alarm::cancel();
+    assert_eq!(status.signal(), Some(nix::libc::SIGHUP));
+}
+
+async_test!(test_pty_child_async, test_pty_child_async_io, {
+    use futures::stream::StreamExt as _;
+
+    let mut child = backend::Command::new("sh")
+        .args(["-c", "stty size; read x; echo \"got $x\""])
+        .spawn_pty(pty_process::Size::new(24, 80))
+        .unwrap();
+    assert!(backend::pid(child.child()).is_some());
+
+    {
+        let (pty_r, mut pty_w) = child.split();
+        let mut output = backend::output(pty_r);
+        assert_eq!(output.next().await.unwrap(), "24 80\r\n");
+        backend::write_all(&mut pty_w, b"foo\n").await;
+        assert_eq!(output.next().await.unwrap(), "foo\r\n");
+        assert_eq!(output.next().await.unwrap(), "got foo\r\n");
+    }
+
+    let status = child.wait().await.unwrap();
+    assert_eq!(status.code().unwrap(), 0);
+});
+
+async_test!(test_pty_child_kill_async, test_pty_child_kill_async_io, {
+    let mut child = backend::Command::new("sleep")
+        .arg("500")
+        .spawn_pty(pty_process::Size::new(24, 80))
+        .unwrap();
+    assert!(child.try_wait().unwrap().is_none());
+    child.kill().await.unwrap();
+    assert!(!child.try_wait().unwrap().unwrap().success());
+});
+
+#[test]
+fn test_pty_child_wait_with_output_blocking() {
+    let child = pty_process::blocking::Command::new("perl")
+        .args(["-e", "print 'x' x 100_000; exit 2"])
+        .spawn_pty(pty_process::Size::new(24, 80))
+        .unwrap();
+    nix::unistd::alarm::set(5);
+    let (status, output) = child.wait_with_output().unwrap();
+    nix::unistd::alarm::cancel();
+    assert_eq!(status.code().unwrap(), 2);
+    assert_eq!(output, vec![b'x'; 100_000]);
+}
+
+#[test]
+fn test_pty_child_command_alive_blocking() {
+    let mut cmd = pty_process::blocking::Command::new("echo");
+    cmd.arg("foo");
+    for _ in 0..2 {
+        // the command still being alive mustn't keep the pty from hanging
+        // up once the child exits
+        let child = cmd.spawn_pty(pty_process::Size::new(24, 80)).unwrap();
+        nix::unistd::alarm::set(5);
+        let (status, output) = child.wait_with_output().unwrap();
+        nix::unistd::alarm::cancel();
+        assert_eq!(status.code().unwrap(), 0);
+        assert_eq!(output, b"foo\r\n");
+    }
+    drop(cmd);
+}
+
+#[test]
+fn test_pty_child_wait_with_output_session_blocking() {
+    let mut cmd = pty_process::blocking::Command::new("sh");
+    cmd.args(["-c", "echo early; sleep 0.2; echo late; exit 3"]);
+    let child = cmd.spawn_pty(pty_process::Size::new(24, 80)).unwrap();
+    // this only returns once the child has exited, even though the command
+    // it was spawned from is still alive
+    nix::unistd::alarm::set(5);
+    let (status, output) = child.wait_with_output().unwrap();
+    nix::unistd::alarm::cancel();
+    assert_eq!(status.code().unwrap(), 3);
+    assert_eq!(output, b"early\r\nlate\r\n");
+    drop(cmd);
+}
+
+#[test]
+fn test_drain_until_hangup_blocking() {
+    let mut pty = pty_process::blocking::Pty::new().unwrap();
+    let pts = pty.pts().unwrap();
+    pty.resize(pty_process::Size::new(24, 80)).unwrap();
+    let mut child = pty_process::blocking::Command::new("sh")
+        .args(["-c", "echo foo; echo bar"])
+        .spawn(&pts)
+        .unwrap();
+    drop(pts);
+
+    // output written before the child exited is still available
+    let status = child.wait().unwrap();
+    assert_eq!(status.code().unwrap(), 0);
+    nix::unistd::alarm::set(5);
+    let output = pty.drain_until_hangup().unwrap();
+    nix::unistd::alarm::cancel();
+    assert_eq!(output, b"foo\r\nbar\r\n");
+}
+
+async_test!(
+    test_pty_child_wait_with_output_async,
+    test_pty_child_wait_with_output_async_io,
+    {
+        let child = backend::Command::new("perl")
+            .args(["-e", "print 'x' x 100_000; exit 2"])
+            .spawn_pty(pty_process::Size::new(24, 80))
+            .unwrap();
+        let (status, output) =
+            backend::timeout(child.wait_with_output()).await;
+        assert_eq!(status.code().unwrap(), 2);
+        assert_eq!(output, vec![b'x'; 100_000]);
+    }
+);
+
+async_test!(
+    test_pty_child_command_alive_async,
+    test_pty_child_command_alive_async_io,
+    {
+        let mut cmd = backend::Command::new("echo");
+        cmd.arg("foo");
+        for _ in 0..2 {
+            let child =
+                cmd.spawn_pty(pty_process::Size::new(24, 80)).unwrap();
+            let (status, output) =
+                backend::timeout(child.wait_with_output()).await;
+            assert_eq!(status.code().unwrap(), 0);
+            assert_eq!(output, b"foo\r\n");
+        }
+        drop(cmd);
+    }
+);
+
+async_test!(
+    test_pty_child_wait_with_output_session_async,
+    test_pty_child_wait_with_output_session_async_io,
+    {
+        let mut cmd = backend::Command::new("sh");
+        cmd.args(["-c", "echo early; sleep 0.2; echo late; exit 3"]);
+        let child = cmd.spawn_pty(pty_process::Size::new(24, 80)).unwrap();
+        let (status, output) =
+            backend::timeout(child.wait_with_output()).await;
+        assert_eq!(status.code().unwrap(), 3);
+        assert_eq!(output, b"early\r\nlate\r\n");
+        drop(cmd);
+    }
+);
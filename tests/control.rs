@@ -0,0 +1,157 @@
+mod helpers;
+
+#[test]
+fn test_send_interrupt_blocking() {
+    let pty = pty_process::blocking::Pty::new().unwrap();
+    let pts = pty.pts().unwrap();
+    pty.resize(pty_process::Size::new(24, 80)).unwrap();
+
+    let mut termios = pty.termios().unwrap();
+    termios.set_control_char(pty_process::ControlChar::Intr, b'X' & 0x1f);
+    pty.set_termios(pty_process::SetArg::Now, &termios).unwrap();
+
+    let mut child = pty_process::blocking::Command::new("perl")
+        .args([
+            "-E",
+            "$|++; $SIG{INT} = sub { say 'INT'; exit 0 }; \
+             say 'started'; sleep 10",
+        ])
+        .spawn(&pts)
+        .unwrap();
+
+    let mut output = helpers::output(&pty);
+    assert_eq!(output.next().unwrap(), "started\r\n");
+    pty.send_interrupt().unwrap();
+    assert_eq!(output.next().unwrap(), "^XINT\r\n");
+
+    let status = child.wait().unwrap();
+    assert_eq!(status.code().unwrap(), 0);
+}
+
+#[test]
+fn test_send_eof_blocking() {
+    use std::io::Write as _;
+
+    let mut pty = pty_process::blocking::Pty::new().unwrap();
+    let pts = pty.pts().unwrap();
+    pty.resize(pty_process::Size::new(24, 80)).unwrap();
+
+    let mut termios = pty.termios().unwrap();
+    termios.set_echo(false);
+    termios.set_control_char(pty_process::ControlChar::Eof, b'X' & 0x1f);
+    pty.set_termios(pty_process::SetArg::Now, &termios).unwrap();
+
+    let mut child = pty_process::blocking::Command::new("cat")
+        .spawn(&pts)
+        .unwrap();
+
+    // at the start of a line
+    pty.write_all(b"foo\n").unwrap();
+    let mut output = helpers::output(&pty);
+    assert_eq!(output.next().unwrap(), "foo\r\n");
+    pty.send_eof().unwrap();
+    nix::unistd::alarm::set(5);
+    let status = child.wait().unwrap();
+    nix::unistd::alarm::cancel();
+    assert_eq!(status.code().unwrap(), 0);
+
+    // after erasing everything on the line, a single eof is enough, and
+    // nothing is left over for the next reader
+    let mut child = pty_process::blocking::Command::new("cat")
+        .spawn(&pts)
+        .unwrap();
+    pty.write_all(b"a").unwrap();
+    pty.send_erase().unwrap();
+    pty.send_eof().unwrap();
+    nix::unistd::alarm::set(5);
+    let status = child.wait().unwrap();
+    nix::unistd::alarm::cancel();
+    assert_eq!(status.code().unwrap(), 0);
+
+    let mut child = pty_process::blocking::Command::new("cat")
+        .spawn(&pts)
+        .unwrap();
+    pty.write_all(b"baz\n").unwrap();
+    let mut output = helpers::output(&pty);
+    assert_eq!(output.next().unwrap(), "baz\r\n");
+    pty.send_eof().unwrap();
+    nix::unistd::alarm::set(5);
+    let status = child.wait().unwrap();
+    nix::unistd::alarm::cancel();
+    assert_eq!(status.code().unwrap(), 0);
+
+    // in the middle of a line, the first eof only sends the partial line
+    let mut child = pty_process::blocking::Command::new("cat")
+        .spawn(&pts)
+        .unwrap();
+    pty.write_all(b"bar").unwrap();
+    pty.send_eof().unwrap();
+    pty.send_eof().unwrap();
+    nix::unistd::alarm::set(5);
+    let status = child.wait().unwrap();
+    nix::unistd::alarm::cancel();
+    assert_eq!(status.code().unwrap(), 0);
+}
+
+#[test]
+fn test_send_erase_blocking() {
+    use std::io::Write as _;
+
+    let mut pty = pty_process::blocking::Pty::new().unwrap();
+    let pts = pty.pts().unwrap();
+    pty.resize(pty_process::Size::new(24, 80)).unwrap();
+
+    let mut termios = pty.termios().unwrap();
+    termios.set_echo(false);
+    pty.set_termios(pty_process::SetArg::Now, &termios).unwrap();
+
+    let mut child = pty_process::blocking::Command::new("head")
+        .args(["-n1"])
+        .spawn(&pts)
+        .unwrap();
+
+    pty.write_all(b"fooo").unwrap();
+    pty.send_erase().unwrap();
+    pty.write_all(b"\n").unwrap();
+    let mut output = helpers::output(&pty);
+    assert_eq!(output.next().unwrap(), "foo\r\n");
+
+    let status = child.wait().unwrap();
+    assert_eq!(status.code().unwrap(), 0);
+}
+
+#[test]
+fn test_disabled_control_char() {
+    let pty = pty_process::blocking::Pty::new().unwrap();
+    let mut termios = pty.termios().unwrap();
+    termios.set_control_char(pty_process::ControlChar::Susp, 0);
+    pty.set_termios(pty_process::SetArg::Now, &termios).unwrap();
+    assert!(pty.send_suspend().is_err());
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_send_interrupt_async() {
+    use futures::stream::StreamExt as _;
+
+    let mut pty = pty_process::Pty::new().unwrap();
+    let pts = pty.pts().unwrap();
+    pty.resize(pty_process::Size::new(24, 80)).unwrap();
+    let mut child = pty_process::Command::new("perl")
+        .args([
+            "-E",
+            "$|++; $SIG{INT} = sub { say 'INT'; exit 0 }; \
+             say 'started'; sleep 10",
+        ])
+        .spawn(&pts)
+        .unwrap();
+
+    let (pty_r, mut pty_w) = pty.split();
+    let mut output = helpers::output_async(pty_r);
+    assert_eq!(output.next().await.unwrap(), "started\r\n");
+    pty_w.send_interrupt().await.unwrap();
+    assert_eq!(output.next().await.unwrap(), "^CINT\r\n");
+
+    let status = child.wait().await.unwrap();
+    assert_eq!(status.code().unwrap(), 0);
+}
@@ -0,0 +1,93 @@
+#[cfg(feature = "expect")]
+const SCRIPT: &str = "$|++; print 'prompt> '; while (<>) { chomp; print \"got $_\\nprompt> \" }";
+
+#[cfg(feature = "expect")]
+#[test]
+fn test_expect_blocking() {
+    let timeout = std::time::Duration::from_secs(5);
+
+    let pty = pty_process::blocking::Pty::new().unwrap();
+    let pts = pty.pts().unwrap();
+    pty.resize(pty_process::Size::new(24, 80)).unwrap();
+    let mut child = pty_process::blocking::Command::new("perl")
+        .args(["-e", SCRIPT])
+        .spawn(&pts)
+        .unwrap();
+    drop(pts);
+
+    let mut session = pty_process::blocking::expect::Session::new(pty);
+    session.expect("prompt> ", timeout).unwrap();
+    assert_eq!(session.before(), b"");
+    assert_eq!(session.after(), b"prompt> ");
+
+    session.send_line("hello").unwrap();
+    let re = regex::bytes::Regex::new(r"got (\w+)").unwrap();
+    session.expect(re, timeout).unwrap();
+    assert_eq!(session.before(), b"hello\r\n");
+    assert_eq!(session.after(), b"got hello");
+
+    let i = session
+        .expect_any(["nonexistent", "prompt> "], timeout)
+        .unwrap();
+    assert_eq!(i, 1);
+    assert_eq!(session.before(), b"\r\n");
+
+    assert!(matches!(
+        session.expect("nonexistent", std::time::Duration::from_millis(100)),
+        Err(pty_process::Error::Timeout)
+    ));
+
+    session.send_control('d').unwrap();
+    session.expect_eof(timeout).unwrap();
+    assert!(matches!(
+        session.expect("prompt> ", timeout),
+        Err(pty_process::Error::Io(e))
+            if e.kind() == std::io::ErrorKind::UnexpectedEof
+    ));
+
+    let status = child.wait().unwrap();
+    assert_eq!(status.code().unwrap(), 0);
+}
+
+#[cfg(all(feature = "async", feature = "expect"))]
+#[tokio::test]
+async fn test_expect_async() {
+    let timeout = std::time::Duration::from_secs(5);
+
+    let pty = pty_process::Pty::new().unwrap();
+    let pts = pty.pts().unwrap();
+    pty.resize(pty_process::Size::new(24, 80)).unwrap();
+    let mut child = pty_process::Command::new("perl")
+        .args(["-e", SCRIPT])
+        .spawn(&pts)
+        .unwrap();
+    drop(pts);
+
+    let mut session = pty_process::expect::Session::new(pty);
+    session.expect("prompt> ", timeout).await.unwrap();
+
+    session.send_line("hello").await.unwrap();
+    let re = regex::bytes::Regex::new(r"got (\w+)").unwrap();
+    session.expect(re, timeout).await.unwrap();
+    assert_eq!(session.before(), b"hello\r\n");
+    assert_eq!(session.after(), b"got hello");
+
+    let i = session
+        .expect_any(["nonexistent", "prompt> "], timeout)
+        .await
+        .unwrap();
+    assert_eq!(i, 1);
+
+    assert!(matches!(
+        session
+            .expect("nonexistent", std::time::Duration::from_millis(100))
+            .await,
+        Err(pty_process::Error::Timeout)
+    ));
+
+    session.send_control('c').await.unwrap();
+    session.expect_eof(timeout).await.unwrap();
+
+    let status = child.wait().await.unwrap();
+    assert_eq!(status.code(), None);
+}
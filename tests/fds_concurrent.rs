@@ -0,0 +1,113 @@
+const THREADS: usize = 8;
+const ITERATIONS: usize = 50;
+const CHILDREN: usize = 500;
+
+// these are in their own test binary since they rely on no other tests
+// opening file descriptors while they run, and take this lock so that they
+// don't interfere with each other either
+static LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+#[test]
+fn test_fds_concurrent() {
+    let _lock = LOCK.lock().unwrap();
+    let fds = check_open_fds();
+    let fds_strings: Vec<String> =
+        fds.iter().map(std::string::ToString::to_string).collect();
+    let expected_output = format!("{}\r\n", fds_strings.join(""));
+
+    let done = std::sync::atomic::AtomicBool::new(false);
+    nix::unistd::alarm::set(60);
+    std::thread::scope(|s| {
+        // keep allocating ptys in other threads, to maximize the chance of
+        // one being open but not yet marked close-on-exec while a child is
+        // being spawned
+        for _ in 0..THREADS {
+            s.spawn(|| {
+                while !done.load(std::sync::atomic::Ordering::SeqCst) {
+                    drop(pty_process::blocking::Pty::new().unwrap());
+                }
+            });
+        }
+        let spawners: Vec<_> = (0..THREADS)
+            .map(|_| {
+                s.spawn(|| {
+                    for _ in 0..ITERATIONS {
+                        let child = pty_process::blocking::Command::new("perl")
+                            .arg("-Efor my $fd (0..255) { open my $fh, \"<&=$fd\"; print $fd if stat $fh }; say")
+                            .spawn_pty(pty_process::Size::new(24, 80))
+                            .unwrap();
+                        let (status, output) =
+                            child.wait_with_output().unwrap();
+                        assert_eq!(status.code().unwrap(), 0);
+                        assert_eq!(
+                            std::string::String::from_utf8(output).unwrap(),
+                            expected_output
+                        );
+                    }
+                })
+            })
+            .collect();
+        let results: Vec<_> =
+            spawners.into_iter().map(|spawner| spawner.join()).collect();
+        done.store(true, std::sync::atomic::Ordering::SeqCst);
+        for result in results {
+            result.unwrap();
+        }
+    });
+    nix::unistd::alarm::cancel();
+
+    assert_eq!(check_open_fds(), fds);
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn test_ptmx_not_inherited() {
+    let _lock = LOCK.lock().unwrap();
+    let done = std::sync::atomic::AtomicBool::new(false);
+    nix::unistd::alarm::set(60);
+    let leaked = std::thread::scope(|s| {
+        for _ in 0..THREADS {
+            s.spawn(|| {
+                while !done.load(std::sync::atomic::Ordering::SeqCst) {
+                    drop(pty_process::blocking::Pty::new().unwrap());
+                }
+            });
+        }
+        // spawn plain children (not attached to any pty) as fast as
+        // possible, and count how many of them ended up with a pty that was
+        // being allocated in another thread at the time. spawn only returns
+        // once the child has exec'd, so any fd which should have been
+        // closed on exec has been by the time we look.
+        let mut leaked = 0;
+        for _ in 0..CHILDREN {
+            let mut child = std::process::Command::new("cat")
+                .stdin(std::process::Stdio::piped())
+                .stdout(std::process::Stdio::null())
+                .spawn()
+                .unwrap();
+            leaked += ptmx_fds(child.id());
+            drop(child.stdin.take());
+            child.wait().unwrap();
+        }
+        done.store(true, std::sync::atomic::Ordering::SeqCst);
+        leaked
+    });
+    nix::unistd::alarm::cancel();
+
+    assert_eq!(leaked, 0);
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn ptmx_fds(pid: u32) -> usize {
+    std::fs::read_dir(format!("/proc/{pid}/fd"))
+        .unwrap()
+        .filter_map(|entry| std::fs::read_link(entry.unwrap().path()).ok())
+        .filter(|target| target.ends_with("ptmx"))
+        .count()
+}
+
+fn check_open_fds() -> Vec<i32> {
+    (0..=255)
+        .filter(|fd| nix::sys::stat::fstat(*fd).is_ok())
+        .collect()
+}
@@ -0,0 +1,81 @@
+#[cfg(feature = "futures-io")]
+#[tokio::test]
+async fn test_futures_io_split() {
+    use futures::io::{AsyncReadExt as _, AsyncWriteExt as _};
+
+    let mut pty = pty_process::Pty::new().unwrap();
+    let pts = pty.pts().unwrap();
+    pty.resize(pty_process::Size::new(24, 80)).unwrap();
+    let mut child = pty_process::Command::new("cat").spawn(&pts).unwrap();
+
+    pty.write_all(b"foo\n").await.unwrap();
+    let mut buf = [0u8; 10];
+    tokio::time::timeout(
+        std::time::Duration::from_secs(5),
+        pty.read_exact(&mut buf),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    assert_eq!(&buf, b"foo\r\nfoo\r\n");
+
+    {
+        let (mut pty_r, mut pty_w) = pty_process::Pty::split(&mut pty);
+        pty_w.write_all(b"bar\n").await.unwrap();
+        tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            pty_r.read_exact(&mut buf),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(&buf, b"bar\r\nbar\r\n");
+    }
+
+    pty.write_all(&[4u8]).await.unwrap();
+    let status = child.wait().await.unwrap();
+    assert_eq!(status.code().unwrap(), 0);
+}
+
+#[cfg(feature = "futures-io")]
+#[tokio::test]
+async fn test_futures_io_into_split() {
+    use futures::io::{AsyncBufReadExt as _, AsyncWriteExt as _};
+
+    let pty = pty_process::Pty::new().unwrap();
+    let pts = pty.pts().unwrap();
+    pty.resize(pty_process::Size::new(24, 80)).unwrap();
+    let mut child = pty_process::Command::new("cat").spawn(&pts).unwrap();
+    drop(pts);
+
+    let (pty_r, mut pty_w) = pty.into_split();
+    pty_w.write_all(b"foo\n").await.unwrap();
+    let mut ptybuf = futures::io::BufReader::new(pty_r);
+    for _ in 0..2 {
+        let mut line = String::new();
+        tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            ptybuf.read_line(&mut line),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(line, "foo\r\n");
+    }
+
+    pty_w.write_all(&[4u8]).await.unwrap();
+    pty_w.close().await.unwrap();
+    let mut rest = vec![];
+    tokio::time::timeout(
+        std::time::Duration::from_secs(5),
+        futures::io::AsyncReadExt::read_to_end(&mut ptybuf, &mut rest),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    assert_eq!(rest, b"");
+
+    let status = child.wait().await.unwrap();
+    assert_eq!(status.code().unwrap(), 0);
+    let _pty = ptybuf.into_inner().unsplit(pty_w).unwrap();
+}
@@ -51,3 +51,208 @@ pub fn output_async<'a>(
         ))
     }))
 }
+
+#[cfg(feature = "async-io")]
+pub fn output_async_io<'a>(
+    pty: impl futures::io::AsyncRead + std::marker::Unpin + 'a,
+) -> std::pin::Pin<Box<dyn futures::stream::Stream<Item = String> + 'a>> {
+    use futures::io::AsyncBufReadExt as _;
+
+    let pty = futures::io::BufReader::new(pty);
+    Box::pin(futures::stream::unfold(pty, |mut pty| async move {
+        let mut buf = vec![];
+        nix::unistd::alarm::set(5);
+        pty.read_until(b'\n', &mut buf).await.unwrap();
+        nix::unistd::alarm::cancel();
+        Some((std::string::String::from_utf8(buf).unwrap(), pty))
+    }))
+}
+
+/// Defines the same test body for both asynchronous backends: as `$tokio`
+/// using the tokio types, and as `$async_io` using the types from
+/// `pty_process::async_io`, so that the two can't drift apart. The body
+/// refers to the types and helpers for the backend under test through
+/// `backend` (either [`tokio_backend`] or [`async_io_backend`]).
+#[macro_export]
+macro_rules! async_test {
+    ($tokio:ident, $async_io:ident, $body:block) => {
+        #[cfg(feature = "async")]
+        #[tokio::test]
+        async fn $tokio() {
+            use $crate::helpers::tokio_backend as backend;
+            $body
+        }
+
+        #[cfg(feature = "async-io")]
+        #[test]
+        fn $async_io() {
+            use $crate::helpers::async_io_backend as backend;
+            async_io::block_on(async $body);
+        }
+    };
+}
+
+#[cfg(feature = "async")]
+#[allow(unused_imports)]
+pub mod tokio_backend {
+    pub use pty_process::{
+        Command, OwnedReadPty, OwnedWritePty, Pty, PtyChild,
+    };
+    pub use tokio::io::BufReader;
+    pub use tokio::net::UnixStream;
+
+    pub fn output<'a>(
+        pty: impl tokio::io::AsyncRead + std::marker::Unpin + 'a,
+    ) -> std::pin::Pin<Box<dyn futures::stream::Stream<Item = String> + 'a>>
+    {
+        super::output_async(pty)
+    }
+
+    pub async fn write_all(
+        pty: &mut (impl tokio::io::AsyncWrite + std::marker::Unpin),
+        buf: &[u8],
+    ) {
+        tokio::io::AsyncWriteExt::write_all(pty, buf).await.unwrap();
+    }
+
+    pub async fn read(
+        pty: &mut (impl tokio::io::AsyncRead + std::marker::Unpin),
+        buf: &mut [u8],
+    ) -> usize {
+        timeout(tokio::io::AsyncReadExt::read(pty, buf)).await
+    }
+
+    pub async fn read_to_end(
+        pty: &mut (impl tokio::io::AsyncRead + std::marker::Unpin),
+    ) -> Vec<u8> {
+        let mut buf = vec![];
+        timeout(tokio::io::AsyncReadExt::read_to_end(pty, &mut buf)).await;
+        buf
+    }
+
+    pub async fn read_line(
+        pty: &mut BufReader<impl tokio::io::AsyncRead + std::marker::Unpin>,
+    ) -> Vec<u8> {
+        let mut buf = vec![];
+        timeout(tokio::io::AsyncBufReadExt::read_until(pty, b'\n', &mut buf))
+            .await;
+        buf
+    }
+
+    pub async fn wait(
+        child: &mut tokio::process::Child,
+    ) -> std::process::ExitStatus {
+        timeout(child.wait()).await
+    }
+
+    pub async fn kill(child: &mut tokio::process::Child) {
+        child.kill().await.unwrap();
+    }
+
+    pub fn pid(child: &tokio::process::Child) -> Option<u32> {
+        child.id()
+    }
+
+    pub fn unsplit_error(
+        res: pty_process::Result<Pty>,
+    ) -> Option<(OwnedReadPty, OwnedWritePty)> {
+        match res {
+            Err(pty_process::Error::Unsplit(r, w)) => Some((r, w)),
+            _ => None,
+        }
+    }
+
+    pub async fn timeout<T, E: std::fmt::Debug>(
+        fut: impl std::future::Future<Output = Result<T, E>>,
+    ) -> T {
+        tokio::time::timeout(std::time::Duration::from_secs(5), fut)
+            .await
+            .unwrap()
+            .unwrap()
+    }
+}
+
+#[cfg(feature = "async-io")]
+#[allow(unused_imports)]
+pub mod async_io_backend {
+    pub use futures::io::BufReader;
+    pub use pty_process::async_io::{
+        Command, OwnedReadPty, OwnedWritePty, Pty, PtyChild,
+    };
+    pub type UnixStream = async_io::Async<std::os::unix::net::UnixStream>;
+
+    pub fn output<'a>(
+        pty: impl futures::io::AsyncRead + std::marker::Unpin + 'a,
+    ) -> std::pin::Pin<Box<dyn futures::stream::Stream<Item = String> + 'a>>
+    {
+        super::output_async_io(pty)
+    }
+
+    pub async fn write_all(
+        pty: &mut (impl futures::io::AsyncWrite + std::marker::Unpin),
+        buf: &[u8],
+    ) {
+        futures::io::AsyncWriteExt::write_all(pty, buf)
+            .await
+            .unwrap();
+    }
+
+    pub async fn read(
+        pty: &mut (impl futures::io::AsyncRead + std::marker::Unpin),
+        buf: &mut [u8],
+    ) -> usize {
+        timeout(futures::io::AsyncReadExt::read(pty, buf)).await
+    }
+
+    pub async fn read_to_end(
+        pty: &mut (impl futures::io::AsyncRead + std::marker::Unpin),
+    ) -> Vec<u8> {
+        let mut buf = vec![];
+        timeout(futures::io::AsyncReadExt::read_to_end(pty, &mut buf)).await;
+        buf
+    }
+
+    pub async fn read_line(
+        pty: &mut BufReader<impl futures::io::AsyncRead + std::marker::Unpin>,
+    ) -> Vec<u8> {
+        let mut buf = vec![];
+        timeout(futures::io::AsyncBufReadExt::read_until(
+            pty, b'\n', &mut buf,
+        ))
+        .await;
+        buf
+    }
+
+    pub async fn wait(
+        child: &mut async_process::Child,
+    ) -> std::process::ExitStatus {
+        timeout(child.status()).await
+    }
+
+    pub async fn kill(child: &mut async_process::Child) {
+        child.kill().unwrap();
+        child.status().await.unwrap();
+    }
+
+    pub fn pid(child: &async_process::Child) -> Option<u32> {
+        Some(child.id())
+    }
+
+    pub fn unsplit_error(
+        res: pty_process::Result<Pty>,
+    ) -> Option<(OwnedReadPty, OwnedWritePty)> {
+        match res {
+            Err(pty_process::Error::AsyncIoUnsplit(r, w)) => Some((r, w)),
+            _ => None,
+        }
+    }
+
+    pub async fn timeout<T, E: std::fmt::Debug>(
+        fut: impl std::future::Future<Output = Result<T, E>>,
+    ) -> T {
+        nix::unistd::alarm::set(5);
+        let res = fut.await.unwrap();
+        nix::unistd::alarm::cancel();
+        res
+    }
+}
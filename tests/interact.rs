@@ -0,0 +1,99 @@
+// interact needs to run with a terminal as stdin and stdout, so these tests
+// work by rerunning the test binary inside a pty, and running the actual
+// test (the *_inner functions) there.
+
+const INNER_ENV: &str = "PTY_PROCESS_INTERACT_INNER";
+const SCRIPT: &str = "read x; stty size; echo \"got $x\"; exit 3";
+
+#[test]
+fn test_interact_blocking() {
+    run_outer("test_interact_blocking_inner");
+}
+
+#[test]
+fn test_interact_blocking_inner() {
+    if std::env::var_os(INNER_ENV).is_none() {
+        return;
+    }
+
+    let mut pty = pty_process::blocking::Pty::new().unwrap();
+    let pts = pty.pts().unwrap();
+    let mut child = pty_process::blocking::Command::new("sh")
+        .args(["-c", SCRIPT])
+        .spawn(&pts)
+        .unwrap();
+    drop(pts);
+
+    let status =
+        pty_process::blocking::interact(&mut pty, &mut child).unwrap();
+    assert_eq!(status.code().unwrap(), 3);
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn test_interact_async() {
+    run_outer("test_interact_async_inner");
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_interact_async_inner() {
+    if std::env::var_os(INNER_ENV).is_none() {
+        return;
+    }
+
+    let mut pty = pty_process::Pty::new().unwrap();
+    let pts = pty.pts().unwrap();
+    let mut child = pty_process::Command::new("sh")
+        .args(["-c", SCRIPT])
+        .spawn(&pts)
+        .unwrap();
+    drop(pts);
+
+    let status = pty_process::interact(&mut pty, &mut child).await.unwrap();
+    assert_eq!(status.code().unwrap(), 3);
+}
+
+fn run_outer(inner: &str) {
+    use std::io::{Read as _, Write as _};
+
+    let mut pty = pty_process::blocking::Pty::new().unwrap();
+    let pts = pty.pts().unwrap();
+    pty.resize(pty_process::Size::new(24, 80)).unwrap();
+    let mut child =
+        pty_process::blocking::Command::new(std::env::current_exe().unwrap())
+            .args([inner, "--exact", "--nocapture"])
+            .env(INNER_ENV, "1")
+            .spawn(&pts)
+            .unwrap();
+    drop(pts);
+
+    // wait for interact to put the terminal into raw mode
+    nix::unistd::alarm::set(10);
+    while pty.termios().unwrap().canonical() {
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    pty.write_all(b"hello\r").unwrap();
+    let mut output = vec![];
+    let mut buf = [0_u8; 4096];
+    loop {
+        match pty.read(&mut buf).unwrap() {
+            0 => break,
+            bytes => output.extend_from_slice(&buf[..bytes]),
+        }
+    }
+    nix::unistd::alarm::cancel();
+
+    let status = child.wait().unwrap();
+    let output = String::from_utf8_lossy(&output);
+    assert!(status.success(), "{output}");
+    assert!(contains(output.as_bytes(), b"24 80"), "{output}");
+    assert!(contains(output.as_bytes(), b"got hello"), "{output}");
+    assert!(pty.termios().unwrap().canonical());
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack
+        .windows(needle.len())
+        .any(|window| window == needle)
+}
@@ -0,0 +1,62 @@
+#[cfg(feature = "mio")]
+#[test]
+fn test_mio() {
+    use std::io::{Read as _, Write as _};
+
+    const PTY: mio::Token = mio::Token(0);
+
+    let mut pty = pty_process::blocking::Pty::new().unwrap();
+    pty.set_nonblocking(true).unwrap();
+    let pts = pty.pts().unwrap();
+    pty.resize(pty_process::Size::new(24, 80)).unwrap();
+    let mut child = pty_process::blocking::Command::new("cat")
+        .spawn(&pts)
+        .unwrap();
+    drop(pts);
+
+    let mut buf = [0u8; 64];
+    assert_eq!(
+        pty.read(&mut buf).unwrap_err().kind(),
+        std::io::ErrorKind::WouldBlock
+    );
+
+    let mut poll = mio::Poll::new().unwrap();
+    let mut events = mio::Events::with_capacity(8);
+    poll.registry()
+        .register(&mut pty, PTY, mio::Interest::READABLE)
+        .unwrap();
+
+    pty.write_all(b"foo\n").unwrap();
+
+    let mut output = vec![];
+    let mut eof = false;
+    nix::unistd::alarm::set(5);
+    while !eof && output != b"foo\r\nfoo\r\n" {
+        poll.poll(&mut events, None).unwrap();
+        for event in &events {
+            assert_eq!(event.token(), PTY);
+            // mio is edge-triggered, so we have to read until we would block
+            loop {
+                match pty.read(&mut buf) {
+                    Ok(0) => {
+                        eof = true;
+                        break;
+                    }
+                    Ok(bytes) => output.extend_from_slice(&buf[..bytes]),
+                    Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                        break
+                    }
+                    Err(e) => panic!("{e}"),
+                }
+            }
+        }
+    }
+    nix::unistd::alarm::cancel();
+    assert_eq!(output, b"foo\r\nfoo\r\n");
+
+    poll.registry().deregister(&mut pty).unwrap();
+    pty.set_nonblocking(false).unwrap();
+    pty.write_all(&[4u8]).unwrap();
+    let status = child.wait().unwrap();
+    assert_eq!(status.code().unwrap(), 0);
+}
@@ -0,0 +1,192 @@
+use pty_process::blocking::packet::{InputMode, Packet, PacketEvent};
+
+fn next_control(
+    reader: &mut pty_process::blocking::packet::PacketReader<
+        &pty_process::blocking::Pty,
+    >,
+) -> Vec<PacketEvent> {
+    nix::unistd::alarm::set(5);
+    let events = loop {
+        match reader.read_packet().unwrap().unwrap() {
+            Packet::Data(_) | Packet::ModeChanged(_) => {}
+            Packet::Control(events) => break events,
+        }
+    };
+    nix::unistd::alarm::cancel();
+    events
+}
+
+#[test]
+fn test_decode() {
+    assert_eq!(Packet::decode(b""), None);
+    assert_eq!(
+        Packet::decode(b"\x00foo"),
+        Some(Packet::Data(b"foo".to_vec()))
+    );
+    assert_eq!(
+        Packet::decode(b"\x03"),
+        Some(Packet::Control(vec![
+            PacketEvent::FlushRead,
+            PacketEvent::FlushWrite
+        ]))
+    );
+    assert_eq!(
+        Packet::decode(b"\x40"),
+        Some(Packet::Control(vec![PacketEvent::IoctlChanged]))
+    );
+}
+
+#[test]
+fn test_packet_mode_blocking() {
+    use std::io::Write as _;
+
+    let pty = pty_process::blocking::Pty::new().unwrap();
+    let pts = pty.pts().unwrap();
+    pty.resize(pty_process::Size::new(24, 80)).unwrap();
+    pty.set_packet_mode(true).unwrap();
+    let mut reader = pty_process::blocking::packet::PacketReader::new(&pty);
+
+    let mut termios = pty.termios().unwrap();
+    termios.set_ixon(false);
+    pty.set_termios(pty_process::SetArg::Now, &termios).unwrap();
+    assert_eq!(next_control(&mut reader), [PacketEvent::NoStop]);
+    termios.set_ixon(true);
+    pty.set_termios(pty_process::SetArg::Now, &termios).unwrap();
+    assert_eq!(next_control(&mut reader), [PacketEvent::DoStop]);
+
+    (&pty).write_all(b"\x13").unwrap();
+    assert_eq!(next_control(&mut reader), [PacketEvent::Stop]);
+    (&pty).write_all(b"\x11").unwrap();
+    assert_eq!(next_control(&mut reader), [PacketEvent::Start]);
+
+    let mut child = pty_process::blocking::Command::new("perl")
+        .args(["-MPOSIX", "-E", "tcflush 0, TCIFLUSH; say 'done'"])
+        .spawn(&pts)
+        .unwrap();
+    drop(pts);
+    assert_eq!(next_control(&mut reader), [PacketEvent::FlushRead]);
+
+    let mut output = vec![];
+    nix::unistd::alarm::set(5);
+    while let Some(packet) = reader.read_packet().unwrap() {
+        if let Packet::Data(data) = packet {
+            output.extend_from_slice(&data);
+        }
+    }
+    nix::unistd::alarm::cancel();
+    assert_eq!(output, b"done\r\n");
+
+    let status = child.wait().unwrap();
+    assert_eq!(status.code().unwrap(), 0);
+}
+
+#[test]
+fn test_mode_changes_blocking() {
+    use std::io::Write as _;
+
+    let pty = pty_process::blocking::Pty::new().unwrap();
+    let pts = pty.pts().unwrap();
+    pty.resize(pty_process::Size::new(24, 80)).unwrap();
+    pty.set_packet_mode(true).unwrap();
+    let mut reader = pty_process::blocking::packet::PacketReader::new(&pty);
+
+    let mut termios = pty.termios().unwrap();
+    assert!(!termios.extproc());
+    termios.set_extproc(true);
+    pty.set_termios(pty_process::SetArg::Now, &termios).unwrap();
+    assert!(pty.termios().unwrap().extproc());
+    assert_eq!(next_control(&mut reader), [PacketEvent::IoctlChanged]);
+
+    let mut child = pty_process::blocking::Command::new("sh")
+        .args([
+            "-c",
+            "stty -icanon; echo raw; head -c1 >/dev/null; \
+             stty icanon; echo cooked",
+        ])
+        .spawn(&pts)
+        .unwrap();
+    drop(pts);
+
+    let mut next_mode = || {
+        nix::unistd::alarm::set(5);
+        let mode = loop {
+            if let Packet::ModeChanged(mode) =
+                reader.read_packet().unwrap().unwrap()
+            {
+                break mode;
+            }
+        };
+        nix::unistd::alarm::cancel();
+        mode
+    };
+    assert_eq!(next_mode(), InputMode::Raw);
+    (&pty).write_all(b"x").unwrap();
+    assert_eq!(next_mode(), InputMode::Canonical);
+
+    let status = child.wait().unwrap();
+    assert_eq!(status.code().unwrap(), 0);
+}
+
+#[cfg(feature = "async")]
+async fn next_async(
+    reader: &mut pty_process::packet::PacketReader<pty_process::OwnedReadPty>,
+) -> Option<Packet> {
+    tokio::time::timeout(
+        std::time::Duration::from_secs(5),
+        reader.read_packet(),
+    )
+    .await
+    .unwrap()
+    .unwrap()
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_packet_mode_async() {
+    let pty = pty_process::Pty::new().unwrap();
+    let pts = pty.pts().unwrap();
+    pty.resize(pty_process::Size::new(24, 80)).unwrap();
+    let mut child = pty_process::Command::new("perl")
+        .args(["-E", "$|++; say 'ready'; <STDIN>; say 'done'"])
+        .spawn(&pts)
+        .unwrap();
+    drop(pts);
+
+    let (pty_r, mut pty_w) = pty.into_split();
+    pty_w.set_packet_mode(true).unwrap();
+    let mut reader = pty_process::packet::PacketReader::new(pty_r);
+
+    let mut output = vec![];
+    while !output.ends_with(b"ready\r\n") {
+        match next_async(&mut reader).await.unwrap() {
+            Packet::Data(data) => output.extend_from_slice(&data),
+            packet => panic!("unexpected {packet:?}"),
+        }
+    }
+
+    tokio::io::AsyncWriteExt::write_all(&mut pty_w, b"\x13")
+        .await
+        .unwrap();
+    assert_eq!(
+        next_async(&mut reader).await.unwrap(),
+        Packet::Control(vec![PacketEvent::Stop])
+    );
+    tokio::io::AsyncWriteExt::write_all(&mut pty_w, b"\x11\n")
+        .await
+        .unwrap();
+    assert_eq!(
+        next_async(&mut reader).await.unwrap(),
+        Packet::Control(vec![PacketEvent::Start])
+    );
+
+    let mut output = vec![];
+    while let Some(packet) = next_async(&mut reader).await {
+        if let Packet::Data(data) = packet {
+            output.extend_from_slice(&data);
+        }
+    }
+    assert_eq!(output, b"\r\ndone\r\n");
+
+    let status = child.wait().await.unwrap();
+    assert_eq!(status.code().unwrap(), 0);
+}
@@ -0,0 +1,106 @@
+mod helpers;
+
+#[test]
+fn test_pts_path_blocking() {
+    let pty = pty_process::blocking::Pty::new().unwrap();
+    let pts = pty.pts().unwrap();
+    pty.resize(pty_process::Size::new(24, 80)).unwrap();
+
+    let index = pty.pts_index().unwrap();
+    #[cfg(target_os = "linux")]
+    assert_eq!(
+        pts.path(),
+        std::path::Path::new(&format!("/dev/pts/{index}"))
+    );
+    assert!(pts.path().to_str().unwrap().ends_with(&index.to_string()));
+
+    let mut child = pty_process::blocking::Command::new("tty")
+        .spawn(&pts)
+        .unwrap();
+    let mut output = helpers::output(&pty);
+    assert_eq!(
+        output.next().unwrap(),
+        format!("{}\r\n", pts.path().display())
+    );
+
+    let status = child.wait().unwrap();
+    assert_eq!(status.code().unwrap(), 0);
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_pts_path_async() {
+    use futures::stream::StreamExt as _;
+
+    let pty = pty_process::Pty::new().unwrap();
+    let pts = pty.pts().unwrap();
+    pty.resize(pty_process::Size::new(24, 80)).unwrap();
+    let index = pty.pts_index().unwrap();
+    assert!(pts.path().to_str().unwrap().ends_with(&index.to_string()));
+
+    let mut child = pty_process::Command::new("tty").spawn(&pts).unwrap();
+    let mut output = helpers::output_async(pty);
+    assert_eq!(
+        output.next().await.unwrap(),
+        format!("{}\r\n", pts.path().display())
+    );
+
+    let status = child.wait().await.unwrap();
+    assert_eq!(status.code().unwrap(), 0);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_open_at_blocking() {
+    // the ptmx node inside the devpts instance is usually only accessible
+    // to root
+    let mut ptmx_paths = vec!["/dev/ptmx"];
+    if std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/pts/ptmx")
+        .is_ok()
+    {
+        ptmx_paths.push("/dev/pts/ptmx");
+    }
+
+    for ptmx in ptmx_paths {
+        let pty = pty_process::blocking::Pty::builder()
+            .ptmx(ptmx)
+            .size(pty_process::Size::new(24, 80))
+            .build()
+            .unwrap();
+        let pts = pty.pts().unwrap();
+        let index = pty.pts_index().unwrap();
+        assert_eq!(
+            pts.path(),
+            std::path::Path::new(&format!("/dev/pts/{index}"))
+        );
+
+        let mut child = pty_process::blocking::Command::new("tty")
+            .spawn(&pts)
+            .unwrap();
+        let mut output = helpers::output(&pty);
+        assert_eq!(
+            output.next().unwrap(),
+            format!("{}\r\n", pts.path().display())
+        );
+        let status = child.wait().unwrap();
+        assert_eq!(status.code().unwrap(), 0);
+    }
+
+    assert!(pty_process::blocking::Pty::open_at("/dev/null").is_err());
+}
+
+#[cfg(all(target_os = "linux", feature = "async"))]
+#[tokio::test]
+async fn test_open_at_async() {
+    let pty = pty_process::Pty::open_at("/dev/ptmx").unwrap();
+    let pts = pty.pts().unwrap();
+    let index = pty.pts_index().unwrap();
+    assert_eq!(
+        pts.path(),
+        std::path::Path::new(&format!("/dev/pts/{index}"))
+    );
+    assert!(pty_process::Pty::open_at("/dev/null").is_err());
+}
@@ -0,0 +1,127 @@
+mod helpers;
+
+// the resize forwarder watches the terminal attached to stdin, so these
+// tests work by rerunning the test binary inside a pty, and running the
+// actual test (the *_inner functions) there.
+
+const INNER_ENV: &str = "PTY_PROCESS_RESIZE_INNER";
+
+#[test]
+fn test_resize_forwarder_blocking() {
+    run_outer("test_resize_forwarder_blocking_inner");
+}
+
+#[test]
+fn test_resize_forwarder_blocking_inner() {
+    if std::env::var_os(INNER_ENV).is_none() {
+        return;
+    }
+
+    let pty = pty_process::blocking::Pty::new().unwrap();
+    let forwarder = pty_process::blocking::ResizeForwarder::new().unwrap();
+    forwarder.add(&pty).unwrap();
+    assert_eq!(pty.size().unwrap(), pty_process::Size::new(24, 80));
+    println!("ready");
+
+    nix::unistd::alarm::set(5);
+    while pty.size().unwrap() != pty_process::Size::new(30, 100) {
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    nix::unistd::alarm::cancel();
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn test_resize_forwarder_async() {
+    run_outer("test_resize_forwarder_async_inner");
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_resize_forwarder_async_inner() {
+    if std::env::var_os(INNER_ENV).is_none() {
+        return;
+    }
+
+    let pty = pty_process::Pty::new().unwrap();
+    let (_pty_r, pty_w) = pty.into_split();
+    let forwarder = pty_process::ResizeForwarder::new().unwrap();
+    forwarder.add(&pty_w).unwrap();
+    assert_eq!(pty_w.size().unwrap(), pty_process::Size::new(24, 80));
+    println!("ready");
+
+    tokio::time::timeout(std::time::Duration::from_secs(5), async {
+        while pty_w.size().unwrap() != pty_process::Size::new(30, 100) {
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap();
+}
+
+#[test]
+fn test_resize_forwarder_remove_blocking() {
+    let pty = pty_process::blocking::Pty::new().unwrap();
+    let pts = pty.pts().unwrap();
+    let mut child = pty_process::blocking::Command::new("cat")
+        .spawn(&pts)
+        .unwrap();
+    drop(pts);
+
+    let forwarder = pty_process::blocking::ResizeForwarder::new().unwrap();
+    let target = forwarder.add(&pty).unwrap();
+    assert!(forwarder.remove(target));
+    assert!(!forwarder.remove(target));
+
+    // the forwarder no longer holds the pty open, so closing it hangs up
+    // the child
+    drop(pty);
+    nix::unistd::alarm::set(5);
+    child.wait().unwrap();
+    nix::unistd::alarm::cancel();
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_resize_forwarder_remove_async() {
+    let pty = pty_process::Pty::new().unwrap();
+    let pts = pty.pts().unwrap();
+    let mut child = pty_process::Command::new("cat").spawn(&pts).unwrap();
+    drop(pts);
+
+    let forwarder = pty_process::ResizeForwarder::new().unwrap();
+    let target = forwarder.add(&pty).unwrap();
+    assert!(forwarder.remove(target));
+    assert!(!forwarder.remove(target));
+
+    drop(pty);
+    tokio::time::timeout(std::time::Duration::from_secs(5), child.wait())
+        .await
+        .unwrap()
+        .unwrap();
+}
+
+fn run_outer(inner: &str) {
+    let pty = pty_process::blocking::Pty::new().unwrap();
+    let pts = pty.pts().unwrap();
+    pty.resize(pty_process::Size::new(24, 80)).unwrap();
+    let mut child =
+        pty_process::blocking::Command::new(std::env::current_exe().unwrap())
+            .args([inner, "--exact", "--nocapture"])
+            .env(INNER_ENV, "1")
+            .spawn(&pts)
+            .unwrap();
+    drop(pts);
+
+    let mut output = helpers::output(&pty);
+    while !output.next().unwrap().ends_with("ready\r\n") {}
+
+    // a burst of resizes should end up with the final size
+    for i in 1..=5 {
+        pty.resize(pty_process::Size::new(24 + i, 80 + i)).unwrap();
+    }
+    pty.resize(pty_process::Size::new(30, 100)).unwrap();
+
+    let status = child.wait().unwrap();
+    assert!(status.success());
+}
@@ -0,0 +1,195 @@
+#[cfg(feature = "screen")]
+const SCRIPT: &str = "print qq{\\e[2J\\e[3;5Hhello\\e[7mworld\\e[m}";
+
+#[cfg(feature = "screen")]
+#[test]
+fn test_screen_blocking() {
+    use std::io::Read as _;
+
+    let pty = pty_process::blocking::Pty::new().unwrap();
+    let pts = pty.pts().unwrap();
+    pty.resize(pty_process::Size::new(24, 80)).unwrap();
+    let mut child = pty_process::blocking::Command::new("perl")
+        .args(["-e", SCRIPT])
+        .spawn(&pts)
+        .unwrap();
+    drop(pts);
+
+    let mut term = pty_process::blocking::screen::Terminal::new(pty).unwrap();
+    assert_eq!(term.screen().size(), (24, 80));
+
+    let mut buf = [0_u8; 4096];
+    nix::unistd::alarm::set(5);
+    while term.read(&mut buf).unwrap() > 0 {}
+    nix::unistd::alarm::cancel();
+
+    let status = child.wait().unwrap();
+    assert_eq!(status.code().unwrap(), 0);
+
+    let screen = term.screen();
+    assert_eq!(screen.contents_between(2, 0, 2, 80), "    helloworld");
+    assert_eq!(screen.cursor_position(), (2, 14));
+    assert!(!screen.cell(2, 8).unwrap().inverse());
+    assert!(screen.cell(2, 9).unwrap().inverse());
+
+    term.resize(pty_process::Size::new(30, 100)).unwrap();
+    assert_eq!(term.screen().size(), (30, 100));
+    assert_eq!(
+        term.get_ref().size().unwrap(),
+        pty_process::Size::new(30, 100)
+    );
+}
+
+#[cfg(all(feature = "async", feature = "screen"))]
+#[tokio::test]
+async fn test_screen_async() {
+    use tokio::io::AsyncReadExt as _;
+
+    let pty = pty_process::Pty::new().unwrap();
+    let pts = pty.pts().unwrap();
+    pty.resize(pty_process::Size::new(24, 80)).unwrap();
+    let mut child = pty_process::Command::new("perl")
+        .args(["-e", SCRIPT])
+        .spawn(&pts)
+        .unwrap();
+    drop(pts);
+
+    let mut term = pty_process::screen::Terminal::new(pty).unwrap();
+
+    let mut buf = [0_u8; 4096];
+    tokio::time::timeout(std::time::Duration::from_secs(5), async {
+        while term.read(&mut buf).await.unwrap() > 0 {}
+    })
+    .await
+    .unwrap();
+
+    let status = child.wait().await.unwrap();
+    assert_eq!(status.code().unwrap(), 0);
+
+    let screen = term.screen();
+    assert_eq!(screen.contents_between(2, 0, 2, 80), "    helloworld");
+    assert_eq!(screen.cursor_position(), (2, 14));
+
+    term.resize(pty_process::Size::new(30, 100)).unwrap();
+    assert_eq!(term.screen().size(), (30, 100));
+}
+
+#[cfg(feature = "screen")]
+#[test]
+fn test_screen_default_size_blocking() {
+    use std::io::Read as _;
+
+    let pty = pty_process::blocking::Pty::new().unwrap();
+    let pts = pty.pts().unwrap();
+    let mut child = pty_process::blocking::Command::new("perl")
+        .args(["-e", SCRIPT])
+        .spawn(&pts)
+        .unwrap();
+    drop(pts);
+
+    let mut term = pty_process::blocking::screen::Terminal::new(pty).unwrap();
+    assert_eq!(term.screen().size(), (24, 80));
+    assert_eq!(
+        term.get_ref().size().unwrap(),
+        pty_process::Size::new(24, 80)
+    );
+
+    let mut buf = [0_u8; 4096];
+    nix::unistd::alarm::set(5);
+    while term.read(&mut buf).unwrap() > 0 {}
+    nix::unistd::alarm::cancel();
+
+    let status = child.wait().unwrap();
+    assert_eq!(status.code().unwrap(), 0);
+    assert_eq!(
+        term.screen().contents_between(2, 0, 2, 80),
+        "    helloworld"
+    );
+}
+
+#[cfg(all(feature = "async", feature = "screen"))]
+#[tokio::test]
+async fn test_screen_default_size_async() {
+    use tokio::io::AsyncReadExt as _;
+
+    let pty = pty_process::Pty::new().unwrap();
+    let pts = pty.pts().unwrap();
+    let mut child = pty_process::Command::new("perl")
+        .args(["-e", SCRIPT])
+        .spawn(&pts)
+        .unwrap();
+    drop(pts);
+
+    let mut term =
+        pty_process::screen::Terminal::with_scrollback(pty, 100).unwrap();
+    assert_eq!(term.screen().size(), (24, 80));
+
+    let mut buf = [0_u8; 4096];
+    tokio::time::timeout(std::time::Duration::from_secs(5), async {
+        while term.read(&mut buf).await.unwrap() > 0 {}
+    })
+    .await
+    .unwrap();
+
+    let status = child.wait().await.unwrap();
+    assert_eq!(status.code().unwrap(), 0);
+    assert_eq!(
+        term.screen().contents_between(2, 0, 2, 80),
+        "    helloworld"
+    );
+}
+
+#[cfg(feature = "screen")]
+#[test]
+fn test_screen_resize_zero_blocking() {
+    let pty = pty_process::blocking::Pty::new().unwrap();
+    pty.resize(pty_process::Size::new(24, 80)).unwrap();
+    let mut term = pty_process::blocking::screen::Terminal::new(pty).unwrap();
+    for size in [
+        pty_process::Size::new(0, 0),
+        pty_process::Size::new(0, 80),
+        pty_process::Size::new(24, 0),
+    ] {
+        assert!(matches!(
+            term.resize(size),
+            Err(pty_process::Error::Io(e))
+                if e.kind() == std::io::ErrorKind::InvalidInput
+        ));
+    }
+    // neither the pty nor the screen was changed
+    assert_eq!(
+        term.get_ref().size().unwrap(),
+        pty_process::Size::new(24, 80)
+    );
+    assert_eq!(term.screen().size(), (24, 80));
+
+    term.resize(pty_process::Size::new(30, 100)).unwrap();
+    assert_eq!(term.screen().size(), (30, 100));
+}
+
+#[cfg(all(feature = "async", feature = "screen"))]
+#[tokio::test]
+async fn test_screen_resize_zero_async() {
+    let pty = pty_process::Pty::new().unwrap();
+    pty.resize(pty_process::Size::new(24, 80)).unwrap();
+    let mut term = pty_process::screen::Terminal::new(pty).unwrap();
+    for size in [
+        pty_process::Size::new(0, 0),
+        pty_process::Size::new(0, 80),
+        pty_process::Size::new(24, 0),
+    ] {
+        assert!(matches!(
+            term.resize(size),
+            Err(pty_process::Error::Io(e))
+                if e.kind() == std::io::ErrorKind::InvalidInput
+        ));
+    }
+    assert_eq!(
+        term.get_ref().size().unwrap(),
+        pty_process::Size::new(24, 80)
+    );
+    assert_eq!(term.screen().size(), (24, 80));
+
+    term.resize(pty_process::Size::new(30, 100)).unwrap();
+    assert_eq!(term.screen().size(), (30, 100));
+}
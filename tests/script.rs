@@ -0,0 +1,195 @@
+#[cfg(feature = "script")]
+#[test]
+fn test_script_blocking() {
+    use std::io::Read as _;
+
+    for format in [
+        pty_process::script::Format::Classic,
+        pty_process::script::Format::Advanced,
+    ] {
+        let pty = pty_process::blocking::Pty::new().unwrap();
+        let pts = pty.pts().unwrap();
+        pty.resize(pty_process::Size::new(24, 80)).unwrap();
+        let mut child = pty_process::blocking::Command::new("echo")
+            .arg("foo")
+            .spawn(&pts)
+            .unwrap();
+        drop(pts);
+
+        let mut recording = pty_process::blocking::script::Recording::new(
+            &pty,
+            vec![],
+            vec![],
+            format,
+            pty.size().unwrap(),
+        )
+        .unwrap();
+        recording
+            .writer()
+            .record_resize(pty_process::Size::new(25, 81))
+            .unwrap();
+        recording.writer().record_input(b"bar").unwrap();
+
+        let mut buf = [0_u8; 4096];
+        nix::unistd::alarm::set(5);
+        while recording.read(&mut buf).unwrap() > 0 {}
+        nix::unistd::alarm::cancel();
+
+        let status = child.wait().unwrap();
+        assert_eq!(status.code().unwrap(), 0);
+
+        let (_, log, timing) = recording.finish(status.code()).unwrap();
+        let log = String::from_utf8(log).unwrap();
+        let timing = String::from_utf8(timing).unwrap();
+        assert!(log.starts_with("Script started on "));
+        assert!(log.ends_with(" [COMMAND_EXIT_CODE=\"0\"]\n"));
+
+        let events: Vec<_> = pty_process::blocking::script::Reader::new(
+            log.as_bytes(),
+            timing.as_bytes(),
+        )
+        .map(|event| event.unwrap().1)
+        .collect();
+        match format {
+            pty_process::script::Format::Classic => {
+                assert!(!timing.starts_with('H'));
+                assert_eq!(
+                    events,
+                    [pty_process::script::Event::Output(b"foo\r\n".to_vec())]
+                );
+            }
+            pty_process::script::Format::Advanced => {
+                assert!(timing.starts_with("H 0.000000 START_TIME "));
+                assert_eq!(
+                    events,
+                    [
+                        pty_process::script::Event::Resize(
+                            pty_process::Size::new(25, 81)
+                        ),
+                        pty_process::script::Event::Input(b"bar".to_vec()),
+                        pty_process::script::Event::Output(
+                            b"foo\r\n".to_vec()
+                        ),
+                    ]
+                );
+            }
+        }
+    }
+}
+
+#[cfg(feature = "script")]
+#[test]
+fn test_script_parse() {
+    let log =
+        "Script started on 2023-08-06 12:35:42+00:00 [COMMAND=\"sh\"]\n\
+               $ ls\r\nfoo\r\n\
+               \nScript done on 2023-08-06 12:35:43+00:00 \
+               [COMMAND_EXIT_CODE=\"0\"]\n";
+    let timing = "H 0.000000 START_TIME 2023-08-06 12:35:42+00:00\n\
+                  H 0.000000 SHELL /bin/bash\n\
+                  O 0.010000 2\n\
+                  I 0.500000 3\n\
+                  O 0.001000 4\n\
+                  S 0.250000 SIGWINCH ROWS=30 COLS=100\n\
+                  S 0.100000 SIGSTOP\n\
+                  O 0.200000 5\n\
+                  H 0.000000 DURATION 1.061\n";
+    let mut reader = pty_process::blocking::script::Reader::new(
+        log.as_bytes(),
+        timing.as_bytes(),
+    );
+    let events: Vec<_> = reader.by_ref().map(Result::unwrap).collect();
+    assert_eq!(
+        events,
+        [
+            (
+                std::time::Duration::from_millis(10),
+                pty_process::script::Event::Output(b"$ ".to_vec())
+            ),
+            (
+                std::time::Duration::from_millis(500),
+                pty_process::script::Event::Input(b"ls\r".to_vec())
+            ),
+            (
+                std::time::Duration::from_millis(1),
+                pty_process::script::Event::Output(b"\nfoo".to_vec())
+            ),
+            (
+                std::time::Duration::from_millis(250),
+                pty_process::script::Event::Resize(pty_process::Size::new(
+                    30, 100
+                ))
+            ),
+            (
+                std::time::Duration::from_millis(300),
+                pty_process::script::Event::Output(b"\r\n\nSc".to_vec())
+            ),
+        ]
+    );
+
+    let mut output = vec![];
+    pty_process::blocking::script::Reader::new(
+        "Script started on 2023-08-06\nabcdef".as_bytes(),
+        "0.001 3\n0.001 2\n".as_bytes(),
+    )
+    .replay(&mut output, 10.0)
+    .unwrap();
+    assert_eq!(output, b"abcde");
+
+    for divisor in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+        let mut output = vec![];
+        let err = pty_process::blocking::script::Reader::new(
+            "abcdef".as_bytes(),
+            "0.001 3\n".as_bytes(),
+        )
+        .replay(&mut output, divisor)
+        .unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        assert!(output.is_empty());
+    }
+}
+
+#[cfg(all(feature = "async", feature = "script"))]
+#[tokio::test]
+async fn test_script_async() {
+    use tokio::io::AsyncReadExt as _;
+
+    let mut pty = pty_process::Pty::new().unwrap();
+    let pts = pty.pts().unwrap();
+    pty.resize(pty_process::Size::new(24, 80)).unwrap();
+    let mut child = pty_process::Command::new("echo")
+        .arg("foo")
+        .spawn(&pts)
+        .unwrap();
+    drop(pts);
+
+    let size = pty.size().unwrap();
+    let (pty_r, _) = pty.split();
+    let mut recording = pty_process::script::Recording::new(
+        pty_r,
+        vec![],
+        vec![],
+        pty_process::script::Format::Advanced,
+        size,
+    )
+    .unwrap();
+    let mut buf = [0_u8; 4096];
+    tokio::time::timeout(std::time::Duration::from_secs(5), async {
+        while recording.read(&mut buf).await.unwrap() > 0 {}
+    })
+    .await
+    .unwrap();
+
+    let status = child.wait().await.unwrap();
+    assert_eq!(status.code().unwrap(), 0);
+
+    let (_, log, timing) = recording.finish(None).await.unwrap();
+    let events: Vec<_> =
+        pty_process::script::Reader::new(&log[..], &timing[..])
+            .map(|event| event.unwrap().1)
+            .collect();
+    assert_eq!(
+        events,
+        [pty_process::script::Event::Output(b"foo\r\n".to_vec())]
+    );
+}
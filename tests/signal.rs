@@ -0,0 +1,66 @@
+mod helpers;
+
+// with job control enabled, the shell runs perl in a new process group and
+// makes that the foreground process group of the pty
+const SCRIPT: &str = "set -m; perl -E '$|++; \
+    $SIG{INT} = sub { say q(INT); exit 0 }; \
+    $SIG{TERM} = sub { say q(TERM); exit 0 }; \
+    say getpgrp; sleep 10'; \
+    echo done";
+
+#[test]
+fn test_signal_foreground_blocking() {
+    for (signal, expected) in [
+        (pty_process::Signal::Int, "INT\r\n"),
+        (pty_process::Signal::Term, "TERM\r\n"),
+    ] {
+        let pty = pty_process::blocking::Pty::new().unwrap();
+        let pts = pty.pts().unwrap();
+        pty.resize(pty_process::Size::new(24, 80)).unwrap();
+        let mut child = pty_process::blocking::Command::new("sh")
+            .args(["-c", SCRIPT])
+            .spawn(&pts)
+            .unwrap();
+
+        let mut output = helpers::output(&pty);
+        let pgrp: u32 = output.next().unwrap().trim().parse().unwrap();
+        assert_ne!(pgrp, child.id());
+        assert_eq!(pty.foreground_process_group().unwrap(), pgrp);
+        assert_eq!(pty.session_id().unwrap(), child.id());
+
+        pty.signal_foreground(signal).unwrap();
+        assert_eq!(output.next().unwrap(), expected);
+        assert_eq!(output.next().unwrap(), "done\r\n");
+
+        let status = child.wait().unwrap();
+        assert_eq!(status.code().unwrap(), 0);
+    }
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_signal_foreground_async() {
+    use futures::stream::StreamExt as _;
+
+    let mut pty = pty_process::Pty::new().unwrap();
+    let pts = pty.pts().unwrap();
+    pty.resize(pty_process::Size::new(24, 80)).unwrap();
+    let mut child = pty_process::Command::new("sh")
+        .args(["-c", SCRIPT])
+        .spawn(&pts)
+        .unwrap();
+
+    let (pty_r, pty_w) = pty.split();
+    let mut output = helpers::output_async(pty_r);
+    let pgrp: u32 = output.next().await.unwrap().trim().parse().unwrap();
+    assert_ne!(Some(pgrp), child.id());
+    assert_eq!(pty_w.foreground_process_group().unwrap(), pgrp);
+    assert_eq!(Some(pty_w.session_id().unwrap()), child.id());
+
+    pty_w.signal_foreground(pty_process::Signal::Int).unwrap();
+    assert_eq!(output.next().await.unwrap(), "INT\r\n");
+    assert_eq!(output.next().await.unwrap(), "done\r\n");
+
+    let status = child.wait().await.unwrap();
+    assert_eq!(status.code().unwrap(), 0);
+}
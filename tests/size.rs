@@ -0,0 +1,62 @@
+#[test]
+fn test_size_blocking() {
+    let pty = pty_process::blocking::Pty::new().unwrap();
+    let pts = pty.pts().unwrap();
+    pty.resize(pty_process::Size::new_with_pixel(24, 80, 640, 480))
+        .unwrap();
+
+    let size = pty.size().unwrap();
+    assert_eq!(size, pty_process::Size::new_with_pixel(24, 80, 640, 480));
+    assert_eq!(size.rows(), 24);
+    assert_eq!(size.cols(), 80);
+    assert_eq!(size.xpixel(), 640);
+    assert_eq!(size.ypixel(), 480);
+    assert_eq!(pts.size().unwrap(), size);
+
+    let mut child = pty_process::blocking::Command::new("stty")
+        .args(["cols", "100", "rows", "30"])
+        .spawn(&pts)
+        .unwrap();
+    let status = child.wait().unwrap();
+    assert_eq!(status.code().unwrap(), 0);
+
+    let size = pty.size().unwrap();
+    assert_eq!(size.rows(), 30);
+    assert_eq!(size.cols(), 100);
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_size_async() {
+    let mut pty = pty_process::Pty::new().unwrap();
+    let pts = pty.pts().unwrap();
+    pty.resize(pty_process::Size::new(24, 80)).unwrap();
+    assert_eq!(pty.size().unwrap(), pty_process::Size::new(24, 80));
+    assert_eq!(pts.size().unwrap(), pty_process::Size::new(24, 80));
+
+    let (_, pty_w) = pty.split();
+    pty_w.resize(pty_process::Size::new(25, 81)).unwrap();
+    assert_eq!(pty_w.size().unwrap(), pty_process::Size::new(25, 81));
+
+    let (_, pty_w) = pty.into_split();
+    pty_w.resize(pty_process::Size::new(26, 82)).unwrap();
+    assert_eq!(pty_w.size().unwrap(), pty_process::Size::new(26, 82));
+    assert_eq!(pts.size().unwrap(), pty_process::Size::new(26, 82));
+}
+
+#[test]
+fn test_size_conversions() {
+    let size: pty_process::Size = "80x24".parse().unwrap();
+    assert_eq!(size, pty_process::Size::new(24, 80));
+    assert_eq!(size.to_string(), "80x24");
+
+    assert!("80".parse::<pty_process::Size>().is_err());
+    assert!("80x".parse::<pty_process::Size>().is_err());
+    assert!("axb".parse::<pty_process::Size>().is_err());
+
+    let size = pty_process::Size::new_with_pixel(24, 80, 640, 480);
+    let winsize = libc::winsize::from(size);
+    assert_eq!(winsize.ws_row, 24);
+    assert_eq!(winsize.ws_col, 80);
+    assert_eq!(pty_process::Size::from(winsize), size);
+}
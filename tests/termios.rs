@@ -0,0 +1,80 @@
+mod helpers;
+
+#[test]
+fn test_termios_blocking() {
+    use std::io::Write as _;
+
+    let mut pty = pty_process::blocking::Pty::new().unwrap();
+    let pts = pty.pts().unwrap();
+    pty.resize(pty_process::Size::new(24, 80)).unwrap();
+
+    let mut termios = pty.termios().unwrap();
+    assert!(termios.echo());
+    assert!(termios.canonical());
+    assert_eq!(termios.control_char(pty_process::ControlChar::Eof), 4);
+    termios.set_echo(false);
+    termios.set_control_char(pty_process::ControlChar::Eof, b'X' & 0x1f);
+    pty.set_termios(pty_process::SetArg::Now, &termios).unwrap();
+
+    let termios = pty.termios().unwrap();
+    assert!(!termios.echo());
+    assert!(termios.canonical());
+    assert_eq!(
+        termios.control_char(pty_process::ControlChar::Eof),
+        b'X' & 0x1f
+    );
+
+    let mut child = pty_process::blocking::Command::new("cat")
+        .spawn(&pts)
+        .unwrap();
+
+    pty.write_all(b"foo\n").unwrap();
+    pty.write_all(b"bar\n").unwrap();
+
+    let mut output = helpers::output(&pty);
+    assert_eq!(output.next().unwrap(), "foo\r\n");
+    assert_eq!(output.next().unwrap(), "bar\r\n");
+
+    pty.write_all(&[b'X' & 0x1f]).unwrap();
+    let status = child.wait().unwrap();
+    assert_eq!(status.code().unwrap(), 0);
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_termios_async() {
+    use futures::stream::StreamExt as _;
+    use tokio::io::AsyncWriteExt as _;
+
+    let mut pty = pty_process::Pty::new().unwrap();
+    let pts = pty.pts().unwrap();
+    pty.resize(pty_process::Size::new(24, 80)).unwrap();
+
+    let mut termios = pty.termios().unwrap();
+    termios.set_echo(false);
+    pty.set_termios(pty_process::SetArg::Drain, &termios)
+        .unwrap();
+
+    let mut child = pty_process::Command::new("cat").spawn(&pts).unwrap();
+
+    let (pty_r, mut pty_w) = pty.split();
+    assert!(!pty_w.termios().unwrap().echo());
+
+    pty_w.write_all(b"foo\n").await.unwrap();
+    pty_w.write_all(b"bar\n").await.unwrap();
+
+    let mut output = helpers::output_async(pty_r);
+    assert_eq!(output.next().await.unwrap(), "foo\r\n");
+    assert_eq!(output.next().await.unwrap(), "bar\r\n");
+
+    let mut termios = pty_w.termios().unwrap();
+    termios.set_echo(true);
+    pty_w
+        .set_termios(pty_process::SetArg::Flush, &termios)
+        .unwrap();
+    assert!(pty_w.termios().unwrap().echo());
+
+    pty_w.write_all(&[4u8]).await.unwrap();
+    let status = child.wait().await.unwrap();
+    assert_eq!(status.code().unwrap(), 0);
+}
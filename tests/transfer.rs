@@ -0,0 +1,120 @@
+mod helpers;
+
+#[test]
+fn test_transfer_blocking() {
+    use std::io::Write as _;
+
+    let (sender, receiver) = std::os::unix::net::UnixStream::pair().unwrap();
+
+    let pty = pty_process::blocking::Pty::builder()
+        .size(pty_process::Size::new(30, 100))
+        .echo(false)
+        .build()
+        .unwrap();
+    let pts = pty.pts().unwrap();
+    let mut child = pty_process::blocking::Command::new("cat")
+        .spawn(&pts)
+        .unwrap();
+    drop(pts);
+
+    let mut metadata = pty.metadata().unwrap();
+    metadata.set_child_pid(Some(child.id()));
+    pty.send_to(&sender, &metadata).unwrap();
+    let index = pty.pts_index().unwrap();
+    drop(pty);
+
+    nix::unistd::alarm::set(5);
+    let (mut pty, metadata) =
+        pty_process::blocking::Pty::recv_from(&receiver).unwrap();
+    nix::unistd::alarm::cancel();
+    assert_eq!(metadata.size(), pty_process::Size::new(30, 100));
+    assert!(!metadata.termios().echo());
+    assert_eq!(metadata.child_pid(), Some(child.id()));
+    assert_eq!(metadata.pts_index(), index);
+    assert_eq!(pty.pts_index().unwrap(), index);
+    assert_eq!(pty.size().unwrap(), pty_process::Size::new(30, 100));
+
+    pty.write_all(b"foo\n").unwrap();
+    let mut output = helpers::output(&pty);
+    assert_eq!(output.next().unwrap(), "foo\r\n");
+
+    pty.write_all(&[4u8]).unwrap();
+    let status = child.wait().unwrap();
+    assert_eq!(status.code().unwrap(), 0);
+}
+
+#[test]
+fn test_transfer_from_fd_blocking() {
+    let pty = pty_process::blocking::Pty::builder()
+        .nonblocking(true)
+        .build()
+        .unwrap();
+    let fd: std::os::fd::OwnedFd = pty.into();
+    assert!(nonblocking(&fd));
+
+    let pty = pty_process::blocking::Pty::from_fd(fd).unwrap();
+    assert!(!nonblocking(&pty));
+}
+
+fn nonblocking(fd: &impl std::os::fd::AsRawFd) -> bool {
+    let flags =
+        nix::fcntl::fcntl(fd.as_raw_fd(), nix::fcntl::FcntlArg::F_GETFL)
+            .unwrap();
+    nix::fcntl::OFlag::from_bits_truncate(flags)
+        .contains(nix::fcntl::OFlag::O_NONBLOCK)
+}
+
+#[test]
+fn test_transfer_invalid_blocking() {
+    use std::io::Write as _;
+
+    let (mut sender, receiver) =
+        std::os::unix::net::UnixStream::pair().unwrap();
+    sender.write_all(b"not a pty").unwrap();
+    let Err(err) = pty_process::blocking::Pty::recv_from(&receiver) else {
+        panic!("received a pty");
+    };
+    assert!(err.to_string().contains("no pty received"), "{err}");
+
+    drop(sender);
+    let Err(err) = pty_process::blocking::Pty::recv_from(&receiver) else {
+        panic!("received a pty");
+    };
+    assert!(matches!(err, pty_process::Error::Io(ref e)
+        if e.kind() == std::io::ErrorKind::UnexpectedEof));
+}
+
+async_test!(test_transfer_async, test_transfer_async_io, {
+    use futures::stream::StreamExt as _;
+
+    let (sender, receiver) = backend::UnixStream::pair().unwrap();
+
+    let pty = backend::Pty::builder()
+        .size(pty_process::Size::new(30, 100))
+        .echo(false)
+        .build()
+        .unwrap();
+    let pts = pty.pts().unwrap();
+    let mut child = backend::Command::new("cat").spawn(&pts).unwrap();
+    drop(pts);
+
+    let mut metadata = pty.metadata().unwrap();
+    metadata.set_child_pid(backend::pid(&child));
+    pty.send_to(&sender, &metadata).await.unwrap();
+    drop(pty);
+
+    let (pty, metadata) =
+        backend::timeout(backend::Pty::recv_from(&receiver)).await;
+    assert_eq!(metadata.size(), pty_process::Size::new(30, 100));
+    assert_eq!(metadata.child_pid(), backend::pid(&child));
+    assert_eq!(pty.pts_index().unwrap(), metadata.pts_index());
+
+    let (pty_r, mut pty_w) = pty.into_split();
+    backend::write_all(&mut pty_w, b"foo\n").await;
+    let mut output = backend::output(pty_r);
+    assert_eq!(output.next().await.unwrap(), "foo\r\n");
+
+    backend::write_all(&mut pty_w, &[4u8]).await;
+    let status = backend::wait(&mut child).await;
+    assert_eq!(status.code().unwrap(), 0);
+});
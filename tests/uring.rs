@@ -0,0 +1,209 @@
+#[cfg(all(feature = "uring", target_os = "linux"))]
+#[test]
+fn test_cat_uring() {
+    tokio_uring::start(async {
+        let pty = pty_process::uring::Pty::new().unwrap();
+        pty.resize(pty_process::Size::new(24, 80)).unwrap();
+        let mut child = pty_process::Command::new("cat")
+            .spawn(&pty.pts().unwrap())
+            .unwrap();
+
+        let (res, _) = pty.write_all(&b"foo\n"[..]).await;
+        res.unwrap();
+        let mut output = vec![];
+        while !output.ends_with(b"foo\r\nfoo\r\n") {
+            let (res, buf) = tokio::time::timeout(
+                std::time::Duration::from_secs(5),
+                pty.read(Vec::with_capacity(64)),
+            )
+            .await
+            .unwrap();
+            assert!(res.unwrap() > 0);
+            output.extend_from_slice(&buf);
+        }
+        assert_eq!(output, b"foo\r\nfoo\r\n");
+
+        let (res, _) = pty.write_all(vec![4u8]).await;
+        res.unwrap();
+        let status = child.wait().await.unwrap();
+        assert_eq!(status.code().unwrap(), 0);
+    });
+}
+
+#[cfg(all(feature = "uring", target_os = "linux"))]
+#[test]
+fn test_eof_uring() {
+    tokio_uring::start(async {
+        let pty = pty_process::uring::Pty::new().unwrap();
+        let pts = pty.pts().unwrap();
+        let mut child = pty_process::Command::new("echo")
+            .arg("foo")
+            .spawn(&pts)
+            .unwrap();
+        drop(pts);
+
+        let output = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            pty.drain_until_hangup(),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(output, b"foo\r\n");
+
+        let (res, _) = pty.read(Vec::with_capacity(64)).await;
+        assert_eq!(res.unwrap(), 0);
+
+        let status = child.wait().await.unwrap();
+        assert_eq!(status.code().unwrap(), 0);
+    });
+}
+
+#[cfg(all(feature = "uring", target_os = "linux"))]
+#[test]
+fn test_resize_uring() {
+    tokio_uring::start(async {
+        let pty = pty_process::uring::Pty::new().unwrap();
+        pty.resize(pty_process::Size::new(24, 80)).unwrap();
+        assert_eq!(pty.size().unwrap(), pty_process::Size::new(24, 80));
+        pty.resize(pty_process::Size::new(30, 100)).unwrap();
+        assert_eq!(pty.size().unwrap(), pty_process::Size::new(30, 100));
+    });
+}
+
+#[cfg(all(feature = "uring", target_os = "linux"))]
+#[test]
+fn test_fixed_uring() {
+    tokio_uring::start(async {
+        let registry = tokio_uring::buf::fixed::FixedBufRegistry::new(
+            std::iter::repeat_with(|| Vec::with_capacity(64)).take(2),
+        );
+        registry.register().unwrap();
+
+        let pty = pty_process::uring::Pty::new().unwrap();
+        pty.resize(pty_process::Size::new(24, 80)).unwrap();
+        let mut child = pty_process::Command::new("cat")
+            .spawn(&pty.pts().unwrap())
+            .unwrap();
+
+        let mut input = registry.check_out(0).unwrap();
+        tokio_uring::buf::BoundedBufMut::put_slice(&mut input, b"foo\n");
+        let (res, _) = pty.write_fixed(input).await;
+        assert_eq!(res.unwrap(), 4);
+
+        let mut output = vec![];
+        let mut buf = registry.check_out(1).unwrap();
+        while !output.ends_with(b"foo\r\nfoo\r\n") {
+            let (res, returned) = tokio::time::timeout(
+                std::time::Duration::from_secs(5),
+                pty.read_fixed(buf),
+            )
+            .await
+            .unwrap();
+            buf = returned;
+            assert!(res.unwrap() > 0);
+            output.extend_from_slice(&buf);
+        }
+        assert_eq!(output, b"foo\r\nfoo\r\n");
+
+        pty.send_eof().await.unwrap();
+        let status = child.wait().await.unwrap();
+        assert_eq!(status.code().unwrap(), 0);
+    });
+}
+
+#[cfg(all(feature = "uring", target_os = "linux"))]
+#[test]
+fn test_concurrent_uring() {
+    tokio_uring::start(async {
+        let pty = pty_process::uring::Pty::new().unwrap();
+        pty.resize(pty_process::Size::new(24, 80)).unwrap();
+        let mut child = pty_process::Command::new("cat")
+            .spawn(&pty.pts().unwrap())
+            .unwrap();
+
+        // the read is submitted before anything has been written, and
+        // stays in flight while the write goes through
+        let ((res, buf), (write_res, _)) =
+            tokio::time::timeout(std::time::Duration::from_secs(5), async {
+                tokio::join!(pty.read(Vec::with_capacity(64)), async {
+                    tokio::task::yield_now().await;
+                    pty.write_all(&b"foo\n"[..]).await
+                })
+            })
+            .await
+            .unwrap();
+        write_res.unwrap();
+        assert!(res.unwrap() > 0);
+        assert!(b"foo\r\nfoo\r\n".starts_with(&buf));
+
+        pty.send_eof().await.unwrap();
+        let status = child.wait().await.unwrap();
+        assert_eq!(status.code().unwrap(), 0);
+    });
+}
+
+#[cfg(all(feature = "uring", target_os = "linux"))]
+#[test]
+fn test_signal_uring() {
+    use std::os::unix::process::ExitStatusExt as _;
+
+    tokio_uring::start(async {
+        let pty = pty_process::uring::Pty::new().unwrap();
+        let mut child = pty_process::Command::new("sleep")
+            .arg("500")
+            .spawn(&pty.pts().unwrap())
+            .unwrap();
+
+        let pid = child.id().unwrap();
+        assert_eq!(pty.session_id().unwrap(), pid);
+        assert_eq!(pty.foreground_process_group().unwrap(), pid);
+        pty.signal_foreground(pty_process::Signal::Term).unwrap();
+        let status = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            child.wait(),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(status.signal(), Some(nix::libc::SIGTERM));
+    });
+}
+
+#[cfg(all(feature = "uring", target_os = "linux"))]
+#[test]
+fn test_idle_uring() {
+    const PTYS: usize = 16;
+
+    tokio_uring::start(async {
+        let ptys: Vec<_> = (0..PTYS)
+            .map(|_| {
+                let pty = pty_process::uring::Pty::new().unwrap();
+                let pts = pty.pts().unwrap();
+                (pty, pts)
+            })
+            .collect();
+
+        // none of the ptys has any output, so every read stays pending,
+        // and none of them should be waiting in an io_uring worker thread
+        let reads = futures::future::join_all(
+            ptys.iter().map(|(pty, _)| pty.read(Vec::with_capacity(64))),
+        );
+        let count = async {
+            tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+            std::fs::read_dir("/proc/self/task")
+                .unwrap()
+                .filter(|task| {
+                    std::fs::read_to_string(
+                        task.as_ref().unwrap().path().join("comm"),
+                    )
+                    .is_ok_and(|comm| comm.starts_with("iou-wrk"))
+                })
+                .count()
+        };
+        tokio::select! {
+            _ = reads => panic!("read from an idle pty completed"),
+            workers = count => assert_eq!(workers, 0),
+        }
+    });
+}
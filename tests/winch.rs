@@ -26,17 +26,14 @@ fn test_winch_std() {
     assert_eq!(status.code().unwrap(), 0);
 }
 
-#[cfg(feature = "async")]
-#[tokio::test]
-async fn test_winch_async() {
+async_test!(test_winch_async, test_winch_async_io, {
     use futures::stream::StreamExt as _;
-    use tokio::io::AsyncWriteExt as _;
 
-    let mut pty = pty_process::Pty::new().unwrap();
+    let mut pty = backend::Pty::new().unwrap();
     let pts = pty.pts().unwrap();
     pty.resize(pty_process::Size::new(24, 80)).unwrap();
-    let mut child = pty_process::Command::new("perl")
-        .args(&[
+    let mut child = backend::Command::new("perl")
+        .args([
             "-E",
             "$|++; $SIG{WINCH} = sub { say 'WINCH' }; say 'started'; <>",
         ])
@@ -44,13 +41,13 @@ async fn test_winch_async() {
         .unwrap();
 
     let (pty_r, mut pty_w) = pty.split();
-    let mut output = helpers::output_async(pty_r);
+    let mut output = backend::output(pty_r);
     assert_eq!(output.next().await.unwrap(), "started\r\n");
 
     pty_w.resize(pty_process::Size::new(25, 80)).unwrap();
     assert_eq!(output.next().await.unwrap(), "WINCH\r\n");
 
-    pty_w.write_all(b"\n").await.unwrap();
-    let status = child.wait().await.unwrap();
+    backend::write_all(&mut pty_w, b"\n").await;
+    let status = backend::wait(&mut child).await;
     assert_eq!(status.code().unwrap(), 0);
-}
+});